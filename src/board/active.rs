@@ -0,0 +1,110 @@
+//! Tracks which parts of a dense board changed, so stable areas can be
+//! skipped.
+//!
+//! The board is split into chunks one word (64 cells) wide and
+//! [`CHUNK_ROWS`] rows tall. A cell's next state only depends on its 3x3
+//! neighbourhood, so a chunk whose neighbouring chunks all stayed the same
+//! since the last step would come out unchanged again and can be copied over.
+
+use crate::rules::Rule;
+
+pub(super) const CHUNK_ROWS: usize = 32;
+
+#[derive(Clone)]
+pub(super) struct ActiveChunks {
+    /// Chunks that changed since the start of the last step, row by row.
+    changed: Vec<bool>,
+    cols: usize,
+    rows: usize,
+    /// The rule of the last step. Switching rules invalidates everything.
+    last_rule: Option<Rule>,
+    active: usize,
+}
+
+impl ActiveChunks {
+    pub fn new(stride: usize, height: usize) -> Self {
+        let (cols, rows) = (stride, height.div_ceil(CHUNK_ROWS));
+
+        Self {
+            changed: vec![true; cols * rows],
+            cols,
+            rows,
+            last_rule: None,
+            active: cols * rows,
+        }
+    }
+
+    /// Marks the chunk holding an in-bounds cell as changed.
+    pub fn mark(&mut self, x: usize, y: usize) {
+        self.changed[(y / CHUNK_ROWS) * self.cols + x / 64] = true;
+    }
+
+    pub fn mark_all(&mut self) {
+        self.changed.fill(true);
+    }
+
+    /// Number of chunks stepped in the last generation, and the total.
+    pub fn count(&self) -> (usize, usize) {
+        (self.active, self.changed.len())
+    }
+
+    /// Chunks that have to be stepped for `rule`. On topologies other than
+    /// the torus the border chunks are always stepped, as their edges can be
+    /// glued to anywhere along the border.
+    pub fn active(&mut self, rule: Rule, torus: bool) -> Vec<bool> {
+        if self.last_rule != Some(rule) {
+            self.last_rule = Some(rule);
+            self.mark_all();
+        }
+
+        let (cols, rows) = (self.cols as isize, self.rows as isize);
+        let mut active = vec![false; self.changed.len()];
+
+        for cy in 0..rows {
+            for cx in 0..cols {
+                let border = cx == 0 || cy == 0 || cx == cols - 1 || cy == rows - 1;
+
+                if !torus && border {
+                    active[(cy * cols + cx) as usize] = true;
+                }
+
+                if !self.changed[(cy * cols + cx) as usize] {
+                    continue;
+                }
+
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let (x, y) = (cx + dx, cy + dy);
+                        let (x, y) = if torus {
+                            (x.rem_euclid(cols), y.rem_euclid(rows))
+                        } else if (0..cols).contains(&x) && (0..rows).contains(&y) {
+                            (x, y)
+                        } else {
+                            continue;
+                        };
+
+                        active[(y * cols + x) as usize] = true;
+                    }
+                }
+            }
+        }
+
+        self.active = active.iter().filter(|&&a| a).count();
+        active
+    }
+
+    /// Records which chunks differ between two generations of the alive
+    /// plane.
+    pub fn record(&mut self, old: &[u64], new: &[u64]) {
+        self.changed.fill(false);
+
+        for (y, (old_row, new_row)) in old.chunks(self.cols).zip(new.chunks(self.cols)).enumerate()
+        {
+            let changed = &mut self.changed[(y / CHUNK_ROWS) * self.cols..][..self.cols];
+
+            for (changed, (old, new)) in changed.iter_mut().zip(old_row.iter().zip(new_row)) {
+                *changed |= old != new;
+            }
+        }
+    }
+}
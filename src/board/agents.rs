@@ -0,0 +1,131 @@
+//! Agents walking over the board under turmite rules.
+//!
+//! Agents move one after another in the order they were placed, each seeing
+//! the cells as the ones before it left them. An agent that walks off a
+//! bounded board is gone.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Board, Tile};
+use crate::rules::{Heading, Turmite};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Agent {
+    pub x: isize,
+    pub y: isize,
+    pub heading: Heading,
+    pub state: u8,
+}
+
+impl Agent {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self {
+            x,
+            y,
+            heading: Heading::North,
+            state: 0,
+        }
+    }
+}
+
+/// The turmite color of a tile: 0 when dead, 1 when simply alive and the
+/// state for higher colors.
+fn color(tile: Tile) -> u8 {
+    match (tile.alive, tile.state) {
+        (false, _) => 0,
+        (true, 0) => 1,
+        (true, state) => state,
+    }
+}
+
+fn color_tile(color: u8) -> Tile {
+    Tile {
+        alive: color != 0,
+        heat: 0,
+        state: if color > 1 { color } else { 0 },
+    }
+}
+
+impl Board {
+    pub fn agents(&self) -> &[Agent] {
+        &self.agents
+    }
+
+    pub fn agents_mut(&mut self) -> &mut Vec<Agent> {
+        &mut self.agents
+    }
+
+    pub(super) fn step_agents(&mut self, rule: &Turmite) {
+        let mut agents = std::mem::take(&mut self.agents);
+
+        agents.retain_mut(|agent| {
+            let m = rule.step(agent.state, color(self.get(agent.x, agent.y)));
+
+            self.paint(agent.x, agent.y, color_tile(m.write));
+            agent.heading = agent.heading.turn(m.turn);
+            agent.state = m.next;
+
+            let (dx, dy) = agent.heading.delta();
+            let (x, y) = (agent.x + dx, agent.y + dy);
+
+            if self.is_unbounded() {
+                (agent.x, agent.y) = (x, y);
+                return true;
+            }
+
+            match self.wrap_xy(x, y) {
+                Some((x, y)) => {
+                    (agent.x, agent.y) = (x as isize, y as isize);
+                    true
+                }
+                None => false,
+            }
+        });
+
+        self.agents = agents;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Topology,
+        config::Config,
+        rules::{Heading, LANGTONS_ANT},
+    };
+
+    #[test]
+    fn langtons_ant_walks_and_is_saved() {
+        let config = Config {
+            rule: LANGTONS_ANT,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let mut board = Board::with_topology(10, 10, Topology::PlaneDead);
+        board.agents_mut().push(Agent::new(5, 5));
+
+        // Turning right on every dead cell, the ant walks around a square
+        // and comes back where it started.
+        for _ in 0..4 {
+            board.update(&config);
+        }
+        assert_eq!(board.agents(), [Agent::new(5, 5)]);
+        assert_eq!(board.live_bounds(), Some((5, 5, 7, 7)));
+
+        // On a live cell it turns left instead.
+        board.update(&config);
+        assert_eq!(board.agents()[0].heading, Heading::West);
+        assert!(!board.is_alive(5, 5));
+
+        let text = serde_json::to_string(&board).unwrap();
+        let loaded: Board = serde_json::from_str(&text).unwrap();
+        assert_eq!(loaded.agents(), board.agents());
+
+        // Agents leave bounded boards at the edge. This one turns right onto
+        // the east edge.
+        board.agents_mut()[0] = Agent::new(9, 0);
+        board.update(&config);
+        assert!(board.agents().is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+//! Word-parallel stepping for life-like rules.
+//!
+//! Every neighbour direction of a packed row is just the row above, the row
+//! below or the row itself shifted by one bit. Feeding the eight shifted words
+//! through a chain of bit-sliced adders gives a 4-bit neighbour count for 64
+//! cells at once, which is then matched against the rule's B/S tables.
+//! Isotropic rules look each cell up in their neighbourhood table instead.
+
+use std::ops::Range;
+
+use super::{active::CHUNK_ROWS, parallel, Board};
+use crate::rules::{Isotropic, Rule, Rulestring, NEIGHBOURS};
+
+/// Four bit planes holding a neighbour count between 0 and 8 for 64 cells.
+#[derive(Default, Clone, Copy)]
+struct Count([u64; 4]);
+
+impl Count {
+    fn add(&mut self, plane: u64) {
+        let mut carry = plane;
+
+        for bit in self.0.iter_mut() {
+            let next = *bit & carry;
+            *bit ^= carry;
+            carry = next;
+        }
+    }
+
+    fn equals(&self, n: usize) -> u64 {
+        self.0.iter().enumerate().fold(!0, |acc, (i, &bit)| {
+            acc & if (n >> i) & 1 == 1 { bit } else { !bit }
+        })
+    }
+
+    /// Cells whose count is enabled in a B or S table.
+    fn matching(&self, table: &[bool; 9]) -> u64 {
+        table
+            .iter()
+            .enumerate()
+            .filter(|(_, &on)| on)
+            .fold(0, |acc, (n, _)| acc | self.equals(n))
+    }
+}
+
+/// Next state of 64 cells given their current state and the eight shifted
+/// neighbour words.
+pub(super) fn life_word(rule: &Rulestring, neighbours: [u64; 8], alive: u64) -> u64 {
+    let mut count = Count::default();
+
+    for (k, plane) in neighbours.into_iter().enumerate() {
+        if rule.neighbourhood.mask() >> k & 1 == 1 {
+            count.add(plane);
+        }
+    }
+
+    (alive & count.matching(&rule.survive)) | (!alive & count.matching(&rule.spawn))
+}
+
+/// Like [`life_word`], looking each cell's neighbourhood up in an isotropic
+/// rule's table.
+pub(super) fn isotropic_word(rule: &Isotropic, neighbours: [u64; 8], alive: u64) -> u64 {
+    (0..64).fold(0, |out, bit| {
+        let pattern = neighbours
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (k, &word)| acc | ((word >> bit & 1) as u8) << k);
+
+        out | (rule.next(alive >> bit & 1 == 1, pattern) as u64) << bit
+    })
+}
+
+/// Bit `x` of the output holds the cell at `x - 1`, wrapping around the row.
+fn shift_west(row: &[u64], width: usize, out: &mut [u64]) {
+    let last = width - 1;
+    let mut carry = (row[last / 64] >> (last % 64)) & 1;
+
+    for (o, &word) in out.iter_mut().zip(row) {
+        *o = (word << 1) | carry;
+        carry = word >> 63;
+    }
+
+    mask_padding(out, width);
+}
+
+/// Bit `x` of the output holds the cell at `x + 1`, wrapping around the row.
+fn shift_east(row: &[u64], width: usize, out: &mut [u64]) {
+    for i in 0..row.len() {
+        let carry = row.get(i + 1).map_or(0, |next| next << 63);
+        out[i] = (row[i] >> 1) | carry;
+    }
+
+    let last = width - 1;
+    out[last / 64] |= (row[0] & 1) << (last % 64);
+}
+
+/// Clears the bits past `width` in the last word of a row.
+fn mask_padding(row: &mut [u64], width: usize) {
+    if !width.is_multiple_of(64) {
+        if let Some(last) = row.last_mut() {
+            *last &= (1 << (width % 64)) - 1;
+        }
+    }
+}
+
+/// A row together with its west and east shifted copies.
+struct Shifted {
+    west: Vec<u64>,
+    east: Vec<u64>,
+}
+
+impl Shifted {
+    fn new(stride: usize) -> Self {
+        Self {
+            west: vec![0; stride],
+            east: vec![0; stride],
+        }
+    }
+
+    fn load(&mut self, row: &[u64], width: usize) {
+        shift_west(row, width, &mut self.west);
+        shift_east(row, width, &mut self.east);
+    }
+}
+
+impl Board {
+    /// Steps the board on up to `threads` workers. The new generation is
+    /// written to the back buffer, which is then swapped with the front.
+    /// Chunks with no changes around them are copied instead of stepped.
+    pub(super) fn step_life(&mut self, rule: &Rulestring, threads: usize) {
+        self.step_words(Rule::Rulestring(*rule), threads, |neighbours, alive| {
+            life_word(rule, neighbours, alive)
+        });
+    }
+
+    pub(super) fn step_isotropic(&mut self, rule: &Isotropic, threads: usize) {
+        self.step_words(Rule::Isotropic(*rule), threads, |neighbours, alive| {
+            isotropic_word(rule, neighbours, alive)
+        });
+    }
+
+    /// Steps the board with `word` giving the next state of 64 cells, like
+    /// [`life_word`]. `rule` only tells apart which rule last stepped.
+    fn step_words(
+        &mut self,
+        rule: Rule,
+        threads: usize,
+        word: impl Fn([u64; 8], u64) -> u64 + Sync,
+    ) {
+        let active = self.activity.active(rule, self.topology.is_torus());
+        let mut next = std::mem::take(&mut self.next);
+        next.resize(self.alive.len(), 0);
+
+        parallel::for_each_band(&mut next, self.stride, threads, |rows, band| {
+            self.step_rows(&word, &active, rows, band);
+        });
+
+        self.activity.record(&self.alive, &next);
+        self.next = std::mem::replace(&mut self.alive, next);
+    }
+
+    /// Recomputes the border cells for topologies other than the torus, which
+    /// the word-parallel pass always wraps. Runs right after `step_life`,
+    /// while the back buffer still holds the previous generation.
+    pub(super) fn step_edges(&mut self, rule: &Rulestring) {
+        self.step_edges_with(|alive, neighbours| {
+            let table = if alive { &rule.survive } else { &rule.spawn };
+            table[(neighbours & rule.neighbourhood.mask()).count_ones() as usize]
+        });
+    }
+
+    /// [`Board::step_edges`] with `next` giving a cell's next state from its
+    /// current one and its neighbourhood, bit `k` set when `NEIGHBOURS[k]` is
+    /// alive.
+    pub(super) fn step_edges_with(&mut self, next: impl Fn(bool, u8) -> bool) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let was_alive = |x: isize, y: isize| match self.wrap_xy(x, y) {
+            Some((x, y)) => {
+                let (i, mask) = self.bit(x, y);
+                self.next[i] & mask != 0
+            }
+            None => self.topology.outside_alive(),
+        };
+
+        let edges = (0..height).flat_map(|y| {
+            let xs: Vec<isize> = if y == 0 || y == height - 1 {
+                (0..width).collect()
+            } else {
+                vec![0, width - 1]
+            };
+            xs.into_iter().map(move |x| (x, y))
+        });
+
+        let cells: Vec<_> = edges
+            .map(|(x, y)| {
+                let neighbours = NEIGHBOURS
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (dx, dy))| was_alive(x + dx, y + dy))
+                    .fold(0, |acc, (k, _)| acc | 1 << k);
+
+                (x, y, next(was_alive(x, y), neighbours))
+            })
+            .collect();
+
+        for (x, y, alive) in cells {
+            self.set_bit(x as usize, y as usize, alive);
+        }
+    }
+
+    /// Steps `rows` of the board, writing them to `out`, which holds exactly
+    /// those rows.
+    fn step_rows(
+        &self,
+        word: impl Fn([u64; 8], u64) -> u64,
+        active: &[bool],
+        rows: Range<usize>,
+        out: &mut [u64],
+    ) {
+        let (width, height, stride) = (self.width, self.height, self.stride);
+
+        let mut above = Shifted::new(stride);
+        let mut center = Shifted::new(stride);
+        let mut below = Shifted::new(stride);
+
+        for (y, out_row) in rows.zip(out.chunks_mut(stride)) {
+            let active = &active[(y / CHUNK_ROWS) * stride..][..stride];
+
+            if !active.contains(&true) {
+                out_row.copy_from_slice(self.row(y));
+                continue;
+            }
+
+            let above_row = self.row((y + height - 1) % height);
+            let center_row = self.row(y);
+            let below_row = self.row((y + 1) % height);
+
+            above.load(above_row, width);
+            center.load(center_row, width);
+            below.load(below_row, width);
+
+            for (i, out_word) in out_row.iter_mut().enumerate() {
+                if !active[i] {
+                    *out_word = center_row[i];
+                    continue;
+                }
+
+                let neighbours = [
+                    above.west[i],
+                    above_row[i],
+                    above.east[i],
+                    center.west[i],
+                    center.east[i],
+                    below.west[i],
+                    below_row[i],
+                    below.east[i],
+                ];
+
+                *out_word = word(neighbours, center_row[i]);
+            }
+
+            mask_padding(out_row, width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        board::{Topology, TOPOLOGIES},
+        rules::{
+            Neighbourhood::{Hexagonal, Moore, VonNeumann},
+            Rule, CONWAY, MAZE, MAZE_MICE,
+        },
+    };
+
+    /// The original per-cell step, kept as the reference implementation.
+    fn step_per_cell(board: &mut Board, rule: &Rulestring) {
+        let old = board.clone();
+
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                let (x, y) = (x as isize, y as isize);
+                let n = old.count_neighbors(x, y, rule.neighbourhood);
+
+                if old.is_alive(x, y) {
+                    board.set(x, y, rule.survive[n]);
+                } else {
+                    board.set(x, y, rule.spawn[n]);
+                }
+            }
+        }
+    }
+
+    fn random_board(width: usize, height: usize, rng: &mut StdRng) -> Board {
+        let mut board = Board::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                board.set_u(x, y, rng.gen_bool(0.4));
+            }
+        }
+
+        board
+    }
+
+    fn assert_same(a: &Board, b: &Board) {
+        for y in 0..a.height() {
+            for x in 0..a.width() {
+                assert_eq!(a.get_u(x, y).alive, b.get_u(x, y).alive, "cell {x},{y}");
+            }
+        }
+        assert_eq!(a.alive, b.alive, "padding bits must stay clear");
+    }
+
+    fn rules() -> Vec<Rulestring> {
+        let mut rules: Vec<_> = [CONWAY, MAZE, MAZE_MICE]
+            .into_iter()
+            .map(|rule| match rule {
+                Rule::Rulestring(rule) => rule,
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for neighbourhood in [Moore, Hexagonal, VonNeumann, Moore].repeat(2) {
+            rules.push(Rulestring {
+                survive: std::array::from_fn(|_| rng.gen_bool(0.5)),
+                spawn: std::array::from_fn(|_| rng.gen_bool(0.5)),
+                neighbourhood,
+            });
+        }
+
+        // B0 rules flip the empty background, which exercises the padding mask.
+        rules.push(Rulestring {
+            survive: [false, false, true, true, false, false, false, false, true],
+            spawn: [true, false, false, true, false, false, false, false, false],
+            neighbourhood: Hexagonal,
+        });
+
+        rules
+    }
+
+    #[test]
+    fn matches_per_cell_step() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for (width, height) in [
+            (1, 1),
+            (3, 2),
+            (7, 5),
+            (63, 4),
+            (64, 9),
+            (65, 70),
+            (130, 33),
+        ] {
+            for rule in rules() {
+                for &topology in TOPOLOGIES.iter().filter(|t| !t.is_unbounded()) {
+                    let mut fast = random_board(width, height, &mut rng);
+                    fast.set_topology(topology);
+                    let mut slow = fast.clone();
+
+                    for _ in 0..12 {
+                        fast.step_life(&rule, 1);
+                        if topology != Topology::Torus {
+                            fast.step_edges(&rule);
+                        }
+                        step_per_cell(&mut slow, &rule);
+                        assert_same(&fast, &slow);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn isotropic_matches_per_cell_step() {
+        let mut rng = StdRng::seed_from_u64(8);
+
+        for text in [
+            "B3/S23",
+            "B2-a/S12",
+            "B3/S23-a4i",
+            "B2ce3aiy/S2-c3-y",
+            "B0/S8",
+        ] {
+            let rule: Isotropic = text.parse().unwrap();
+
+            for &topology in TOPOLOGIES.iter().filter(|t| !t.is_unbounded()) {
+                let mut fast = random_board(70, 9, &mut rng);
+                fast.set_topology(topology);
+                let mut slow = fast.clone();
+
+                for _ in 0..10 {
+                    fast.step_isotropic(&rule, 2);
+                    if topology != Topology::Torus {
+                        fast.step_edges_with(|alive, neighbours| rule.next(alive, neighbours));
+                    }
+
+                    let old = slow.clone();
+                    for y in 0..9 {
+                        for x in 0..70 {
+                            let neighbours = NEIGHBOURS
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, (dx, dy))| old.is_alive(x + dx, y + dy))
+                                .fold(0, |acc, (k, _)| acc | 1 << k);
+                            slow.set(x, y, rule.next(old.is_alive(x, y), neighbours));
+                        }
+                    }
+
+                    assert_same(&fast, &slow);
+                }
+            }
+        }
+
+        // Without letters an isotropic rule is just the life-like one.
+        let mut life = random_board(100, 50, &mut rng);
+        let mut isotropic = life.clone();
+        for _ in 0..20 {
+            life.step_life(&rules()[0], 1);
+            isotropic.step_isotropic(&"B3/S23".parse().unwrap(), 1);
+        }
+        assert_same(&life, &isotropic);
+    }
+
+    #[test]
+    fn glider_wraps_around_torus() {
+        let mut board = Board::new(70, 6);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(x, y, true);
+        }
+        let start = board.clone();
+
+        let Rule::Rulestring(rule) = CONWAY else {
+            unreachable!()
+        };
+
+        // A glider moves one cell diagonally every four generations, so after
+        // 4 * lcm(70, 6) generations it is back where it started.
+        for _ in 0..4 * 210 {
+            board.step_life(&rule, 1);
+        }
+
+        assert_same(&board, &start);
+    }
+
+    #[test]
+    fn skipping_stable_chunks_matches_full_step() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let conway = rules()[0];
+
+        for &topology in TOPOLOGIES.iter().filter(|t| !t.is_unbounded()) {
+            for rule in [conway, rules()[1], rules()[5]] {
+                // A small soup in a large empty board, so most chunks settle.
+                let mut tracked = Board::with_topology(300, 200, topology);
+                for y in 90..130 {
+                    for x in 100..160 {
+                        tracked.set(x, y, rng.gen_bool(0.4));
+                    }
+                }
+                let mut full = tracked.clone();
+
+                for generation in 0..150 {
+                    // Edits between steps have to wake their chunks up again.
+                    if generation % 50 == 25 {
+                        let (x, y) = (rng.gen_range(0..300), rng.gen_range(0..200));
+                        tracked.draw(x, y, 2, true);
+                        full.draw(x, y, 2, true);
+                    }
+
+                    full.activity.mark_all();
+                    for board in [&mut tracked, &mut full] {
+                        board.step_life(&rule, 3);
+                        if topology != Topology::Torus {
+                            board.step_edges(&rule);
+                        }
+                    }
+
+                    assert_eq!(
+                        tracked.alive, full.alive,
+                        "{topology}, generation {generation}"
+                    );
+                }
+            }
+        }
+
+        let (active, total) = Board::new(300, 200).activity.count();
+        assert_eq!(active, total, "new boards start fully active");
+    }
+
+    #[test]
+    fn settled_board_steps_few_chunks() {
+        let mut board = Board::new(640, 320);
+        // A blinker in one corner and nothing else. Its chunk and the eight
+        // around it, wrapping over the edges, are all that gets stepped.
+        for x in 1..4 {
+            board.set(x, 1, true);
+        }
+
+        for _ in 0..4 {
+            board.step_life(&rules()[0], 1);
+        }
+
+        assert_eq!(board.activity.count(), (9, 100));
+    }
+
+    #[test]
+    fn threaded_step_is_bit_identical() {
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for (width, height) in [(65, 31), (200, 257), (1000, 130)] {
+            for rule in rules() {
+                let mut single = random_board(width, height, &mut rng);
+                let mut threaded = single.clone();
+
+                for _ in 0..6 {
+                    single.step_life(&rule, 1);
+                    threaded.step_life(&rule, 7);
+                    assert_eq!(single.alive, threaded.alive);
+                }
+            }
+        }
+    }
+}
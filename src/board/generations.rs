@@ -0,0 +1,189 @@
+//! Generations rules: cells that die don't turn dead right away but pass
+//! through `states - 2` refractory states first, during which they neither
+//! count as neighbours nor can be born again.
+//!
+//! The alive plane is stepped like any life-like rule. Afterwards births on
+//! refractory cells are undone, fresh deaths start decaying and decaying cells
+//! move one state on.
+
+use super::Board;
+use crate::rules::Generations;
+
+impl Board {
+    pub(super) fn step_generations(&mut self, rule: &Generations, threads: usize) {
+        self.step_life(&rule.life, threads);
+
+        if !self.topology.is_torus() {
+            self.step_edges(&rule.life);
+        }
+
+        self.decay(rule.states);
+    }
+
+    /// Moves dying cells on after the alive plane was stepped, while the back
+    /// buffer still holds the previous generation. Cells that just died start
+    /// decaying if there are more than two `states`.
+    pub(super) fn decay(&mut self, states_count: u8) {
+        let (width, stride) = (self.width, self.stride);
+        let mut states = self
+            .states
+            .take()
+            .unwrap_or_else(|| vec![0; self.width * self.height]);
+
+        for y in 0..self.height {
+            for word in 0..stride {
+                let i = y * stride + word;
+                let x0 = word * 64;
+                let cells = &mut states[y * width + x0..y * width + (x0 + 64).min(width)];
+                let died = self.next[i] & !self.alive[i];
+
+                if died == 0 && cells.iter().all(|&state| state == 0) {
+                    continue;
+                }
+
+                for (bit, state) in cells.iter_mut().enumerate() {
+                    let mask = 1 << bit;
+
+                    if *state != 0 {
+                        *state = if *state + 1 < states_count {
+                            *state + 1
+                        } else {
+                            0
+                        };
+                        self.alive[i] &= !mask;
+                        self.activity.mark(x0 + bit, y);
+                    } else if died & mask != 0 && states_count > 2 {
+                        *state = 2;
+                    }
+                }
+            }
+        }
+
+        self.states = Some(states);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        board::Topology,
+        config::Config,
+        rules::{Neighbourhood, Rule, Rulestring, BRIANS_BRAIN, STAR_WARS},
+    };
+
+    fn step_per_cell(board: &mut Board, rule: &Generations) {
+        let old = board.clone();
+
+        for y in 0..board.height() as isize {
+            for x in 0..board.width() as isize {
+                let tile = old.get(x, y);
+                let n = old.count_neighbors(x, y, rule.life.neighbourhood);
+
+                let (alive, state) = if tile.alive {
+                    match (rule.life.survive[n], rule.states > 2) {
+                        (true, _) => (true, 0),
+                        (false, true) => (false, 2),
+                        (false, false) => (false, 0),
+                    }
+                } else if tile.state != 0 {
+                    (false, (tile.state + 1) % rule.states)
+                } else {
+                    (rule.life.spawn[n], 0)
+                };
+
+                board.set(x, y, alive);
+                board.set_state(x, y, state);
+            }
+        }
+    }
+
+    fn generations(rule: Rule) -> Generations {
+        match rule {
+            Rule::Generations(rule) => rule,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn matches_per_cell_step() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut rules = vec![generations(BRIANS_BRAIN), generations(STAR_WARS)];
+        for (states, neighbourhood) in [
+            (2, Neighbourhood::Moore),
+            (3, Neighbourhood::Hexagonal),
+            (6, Neighbourhood::VonNeumann),
+        ] {
+            rules.push(Generations {
+                life: Rulestring {
+                    survive: std::array::from_fn(|_| rng.gen_bool(0.5)),
+                    spawn: std::array::from_fn(|i| i != 0 && rng.gen_bool(0.4)),
+                    neighbourhood,
+                },
+                states,
+            });
+        }
+
+        for rule in rules {
+            for topology in [Topology::Torus, Topology::PlaneDead, Topology::CrossSurface] {
+                let mut fast = Board::with_topology(70, 40, topology);
+                for y in 0..40 {
+                    for x in 0..70 {
+                        fast.set(x, y, rng.gen_bool(0.3));
+                    }
+                }
+                let mut slow = fast.clone();
+
+                for generation in 0..30 {
+                    fast.step_generations(&rule, 2);
+                    step_per_cell(&mut slow, &rule);
+
+                    for y in 0..40 {
+                        for x in 0..70 {
+                            let (a, b) = (fast.get(x, y), slow.get(x, y));
+                            assert_eq!(
+                                (a.alive, a.state),
+                                (b.alive, b.state),
+                                "{topology} generation {generation}, cell {x},{y}",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn states_are_saved() {
+        let mut board = Board::new(20, 10);
+        board.set(3, 4, true);
+        board.set_state(5, 6, 2);
+
+        let text = serde_json::to_string(&board).unwrap();
+        let loaded: Board = serde_json::from_str(&text).unwrap();
+
+        assert!(loaded.is_alive(3, 4));
+        assert_eq!(loaded.get(5, 6).state, 2);
+        assert_eq!(loaded.get(3, 4).state, 0);
+    }
+
+    #[test]
+    fn unbounded_boards_are_left_alone() {
+        let config = Config {
+            rule: BRIANS_BRAIN,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let mut board = Board::with_topology(20, 10, Topology::Unbounded);
+        for x in 3..6 {
+            board.set(x, 4, true);
+        }
+
+        board.update(&config);
+
+        assert_eq!(board.live_bounds(), Some((3, 4, 6, 5)));
+        assert_eq!(board.generation(), 0);
+    }
+}
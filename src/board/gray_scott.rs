@@ -0,0 +1,156 @@
+//! Reaction-diffusion stepping.
+//!
+//! Like the continuous rules, the chemicals are padded by a cell with what
+//! the topology puts past the edges, where there is only A. Cells with at
+//! least a quarter B count as alive. Cells painted since the last step get
+//! a dose of B and erased ones lose theirs, so the brush injects B and the
+//! eraser washes it out. Unbounded boards don't step.
+
+use rand::Rng;
+
+use super::{parallel, Board};
+use crate::rules::{perlin_grid, worley_cells, GrayScott, Seeding};
+
+/// Weights of the neighbourhood in the Laplacian, north west first.
+const LAPLACIAN: [f32; 9] = [0.05, 0.2, 0.05, 0.2, -1., 0.2, 0.05, 0.2, 0.05];
+
+/// B at which a cell counts as alive.
+const ALIVE: f32 = 0.25;
+
+impl Board {
+    pub(super) fn step_gray_scott(&mut self, rule: &GrayScott, threads: usize) {
+        let (width, height) = (self.width, self.height);
+        let mut chemicals = self
+            .chemicals
+            .take()
+            .unwrap_or_else(|| vec![(1., 0.); width * height]);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (alive, cell) = (self.alive_at(x, y), &mut chemicals[y * width + x]);
+
+                if alive && cell.1 < ALIVE {
+                    *cell = (0.5, 0.5);
+                } else if !alive && cell.1 >= ALIVE {
+                    *cell = (1., 0.);
+                }
+            }
+        }
+
+        let padded_width = width + 2;
+        let mut padded = Vec::with_capacity(padded_width * (height + 2));
+
+        for y in 0..height + 2 {
+            for x in 0..padded_width {
+                padded.push(match self.wrap_xy(x as isize - 1, y as isize - 1) {
+                    Some((x, y)) => chemicals[y * width + x],
+                    None => (1., 0.),
+                });
+            }
+        }
+
+        let mut next = vec![(0., 0.); width * height];
+
+        parallel::for_each_band(&mut next, width, threads, |rows, band| {
+            for (y, row) in rows.zip(band.chunks_mut(width)) {
+                for (x, out) in row.iter_mut().enumerate() {
+                    let mut laplacian = (0., 0.);
+
+                    for (k, weight) in LAPLACIAN.iter().enumerate() {
+                        let (a, b) = padded[(y + k / 3) * padded_width + x + k % 3];
+                        laplacian.0 += a * weight;
+                        laplacian.1 += b * weight;
+                    }
+
+                    *out = rule.react(chemicals[y * width + x], laplacian);
+                }
+            }
+        });
+
+        for y in 0..height {
+            for x in 0..width {
+                self.set_bit(x, y, next[y * width + x].1 >= ALIVE);
+            }
+        }
+
+        self.chemicals = Some(next);
+    }
+
+    /// B in an in-bounds cell, going by the alive plane when nothing has
+    /// reacted yet.
+    pub fn concentration(&self, x: usize, y: usize) -> f32 {
+        match &self.chemicals {
+            Some(chemicals) => chemicals[y * self.width + x].1,
+            None => self.alive_at(x, y) as u8 as f32,
+        }
+    }
+
+    /// Replaces the board with a fresh reaction, with B wherever the seeding
+    /// pattern puts live cells.
+    pub fn seed_chemicals(&mut self, seeding: Seeding) {
+        self.clear();
+
+        let (width, height) = (self.width, self.height);
+        match seeding {
+            Seeding::Perlin => {
+                let seed = self.rng.gen();
+                let grid = perlin_grid(seed, width, height, 0.04);
+
+                for (y, row) in grid.iter().enumerate() {
+                    for (x, value) in row.iter().enumerate() {
+                        if value.abs() > 0.4 {
+                            self.set_u(x, y, true);
+                        }
+                    }
+                }
+            }
+            Seeding::Worley => {
+                let cells = worley_cells(self, 30);
+
+                for y in 0..height {
+                    for x in 0..width {
+                        let point = cells[y * width + x].1;
+                        let edge = (x + 1 < width && cells[y * width + x + 1].1 != point)
+                            || (y + 1 < height && cells[(y + 1) * width + x].1 != point);
+
+                        if edge {
+                            self.set_u(x, y, true);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, rules::MITOSIS};
+
+    #[test]
+    fn spots_divide_from_a_seed() {
+        let config = Config {
+            rule: MITOSIS,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let mut board = Board::new(64, 64);
+        for y in 28..36 {
+            for x in 28..34 {
+                board.set(x, y, true);
+            }
+        }
+
+        for _ in 0..2000 {
+            board.update(&config);
+        }
+
+        let chemicals = board.chemicals.as_ref().unwrap();
+        assert!(chemicals
+            .iter()
+            .all(|&(a, b)| (0. ..=1.).contains(&a) && (0. ..=1.).contains(&b)));
+        let (x0, y0, x1, y1) = board.live_bounds().unwrap();
+        assert!(x1 - x0 > 24 && y1 - y0 > 24, "{:?}", (x0, y0, x1, y1));
+    }
+}
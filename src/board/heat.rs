@@ -0,0 +1,128 @@
+//! Heat-coupled stepping for life-like rules.
+//!
+//! The board steps as the rule says, then births on cells that are too hot
+//! and survivals that would reach the burnout heat are undone. Heat follows
+//! the cells as they were before the step: live ones gain heat and dead ones
+//! lose it.
+
+use super::Board;
+use crate::{config::HeatCoupling, rules::Rulestring};
+
+impl Board {
+    pub fn step_heat_coupled(
+        &mut self,
+        rule: &Rulestring,
+        coupling: &HeatCoupling,
+        threads: usize,
+    ) {
+        if self.sparse.is_some() {
+            self.step_heat_coupled_sparse(rule, coupling, threads);
+            return;
+        }
+
+        // Only the two planes the coupling reads are kept from before the
+        // step, the heat being updated in place.
+        let alive = self.alive.clone();
+        let (width, height) = (self.width, self.height);
+        let mut heat = self.heat.take().unwrap_or_else(|| vec![0; width * height]);
+
+        self.step_life(rule, threads);
+        if !self.topology.is_torus() {
+            self.step_edges(rule);
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let (word, mask) = self.bit(x, y);
+                let (was_alive, heat) = (alive[word] & mask != 0, &mut heat[y * width + x]);
+
+                if self.alive_at(x, y) && coupling.too_hot(was_alive, *heat) {
+                    self.set_bit(x, y, false);
+                }
+                *heat = coupling.next_heat(was_alive, *heat);
+            }
+        }
+
+        self.heat = Some(heat);
+    }
+
+    /// The same on unbounded boards, whose chunks only hold the alive and
+    /// heat planes.
+    fn step_heat_coupled_sparse(
+        &mut self,
+        rule: &Rulestring,
+        coupling: &HeatCoupling,
+        threads: usize,
+    ) {
+        let Some(sparse) = &mut self.sparse else {
+            return;
+        };
+        let old = sparse.clone();
+        sparse.step(rule, threads);
+
+        let everything = (isize::MIN, isize::MIN, isize::MAX, isize::MAX);
+        let mut deaths = vec![];
+        self.for_each_tile_in(everything, |x, y, tile| {
+            let before = old.get(x, y);
+
+            if tile.alive && coupling.too_hot(before.alive, before.heat) {
+                deaths.push((x, y));
+            }
+        });
+
+        for (x, y) in deaths {
+            self.set(x, y, false);
+        }
+
+        old.for_each_tile_in(everything, |x, y, tile| {
+            self.set_heat(x, y, coupling.next_heat(tile.alive, tile.heat));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        rules::{Rule, MAZE},
+    };
+
+    #[test]
+    fn hot_cells_burn_out_and_block_births() {
+        let config = Config {
+            rule: MAZE,
+            couple_heat: true,
+            heat_coupling: HeatCoupling {
+                gain: 100,
+                ..HeatCoupling::MAZE_CYCLE
+            },
+            ..Default::default()
+        };
+        let Rule::Rulestring(maze) = MAZE else {
+            unreachable!()
+        };
+
+        // A block is stable under Maze, until it burns out.
+        let mut board = Board::new(8, 8);
+        for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+            board.set(x, y, true);
+        }
+        board.update(&config);
+        board.update(&config);
+        assert_eq!(board.get(2, 2).heat, 200);
+        assert!(board.is_alive(2, 2));
+        board.update(&config);
+        assert_eq!(board.live_bounds(), None);
+
+        // Three cells would give birth, but not on hot ground.
+        let mut board = Board::new(8, 8);
+        for (x, y) in [(2, 2), (3, 2), (2, 3)] {
+            board.set(x, y, true);
+        }
+        board.set_heat(3, 3, 150);
+        board.step_heat_coupled(&maze, &config.heat_coupling, 1);
+        assert!(!board.is_alive(3, 3));
+        assert_eq!(board.get(3, 3).heat, 149);
+    }
+}
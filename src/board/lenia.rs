@@ -0,0 +1,129 @@
+//! Continuous rule stepping.
+//!
+//! The float field is padded by the kernel radius with whatever the topology
+//! puts past the edges, so the convolution itself is plain indexing. Cells
+//! count as alive from half way up, which keeps the alive plane and the
+//! tools working on it meaningful. Cells painted or erased since the last
+//! step are set to 1 or 0 before stepping.
+//!
+//! The field only covers the board area, so unbounded boards don't step.
+
+use super::{parallel, Board};
+use crate::rules::Lenia;
+
+impl Board {
+    pub(super) fn step_lenia(&mut self, rule: &Lenia, threads: usize) {
+        let (width, height) = (self.width, self.height);
+        let mut field = self
+            .field
+            .take()
+            .unwrap_or_else(|| vec![0.; width * height]);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (alive, value) = (self.alive_at(x, y), &mut field[y * width + x]);
+
+                if alive != (*value >= 0.5) {
+                    *value = if alive { 1. } else { 0. };
+                }
+            }
+        }
+
+        let r = rule.radius as usize;
+        let padded_width = width + 2 * r;
+        let outside = if self.topology.outside_alive() {
+            1.
+        } else {
+            0.
+        };
+        let mut padded = Vec::with_capacity(padded_width * (height + 2 * r));
+
+        for y in 0..height + 2 * r {
+            for x in 0..padded_width {
+                padded.push(
+                    match self.wrap_xy(x as isize - r as isize, y as isize - r as isize) {
+                        Some((x, y)) => field[y * width + x],
+                        None => outside,
+                    },
+                );
+            }
+        }
+
+        // Offsets into the padded field, from a cell's north west corner of
+        // the padding.
+        let kernel: Vec<(usize, f32)> = rule
+            .kernel()
+            .into_iter()
+            .map(|(dx, dy, w)| {
+                let (x, y) = ((dx + r as isize) as usize, (dy + r as isize) as usize);
+                (y * padded_width + x, w)
+            })
+            .collect();
+
+        let mut next = vec![0.; width * height];
+
+        parallel::for_each_band(&mut next, width, threads, |rows, band| {
+            for (y, row) in rows.zip(band.chunks_mut(width)) {
+                for (x, out) in row.iter_mut().enumerate() {
+                    let corner = y * padded_width + x;
+                    let u: f32 = kernel.iter().map(|&(i, w)| padded[corner + i] * w).sum();
+
+                    *out = (field[y * width + x] + rule.dt * rule.growth(u)).clamp(0., 1.);
+                }
+            }
+        });
+
+        for y in 0..height {
+            for x in 0..width {
+                self.set_bit(x, y, next[y * width + x] >= 0.5);
+            }
+        }
+
+        self.field = Some(next);
+    }
+
+    /// Value of an in-bounds cell under continuous rules, going by the alive
+    /// plane when there is no field yet.
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        match &self.field {
+            Some(field) => field[y * self.width + x],
+            None => self.alive_at(x, y) as u8 as f32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, rules::LENIA};
+
+    #[test]
+    fn field_grows_and_is_saved() {
+        let config = Config {
+            rule: LENIA,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let mut board = Board::new(40, 40);
+        for y in 15..25 {
+            for x in 15..25 {
+                board.set(x, y, true);
+            }
+        }
+
+        for _ in 0..5 {
+            board.update(&config);
+        }
+
+        let field = board.field.clone().unwrap();
+        assert!(field.iter().all(|v| (0. ..=1.).contains(v)));
+        assert!(field.iter().any(|&v| v > 0. && v < 1.));
+        for (i, &v) in field.iter().enumerate() {
+            assert_eq!(board.alive_at(i % 40, i / 40), v >= 0.5);
+        }
+
+        let text = serde_json::to_string(&board).unwrap();
+        let loaded: Board = serde_json::from_str(&text).unwrap();
+        assert_eq!(loaded.field, Some(field));
+    }
+}
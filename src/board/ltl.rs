@@ -0,0 +1,189 @@
+//! Larger than Life stepping.
+//!
+//! Neighbour counts come from a summed-area table over the board, padded by
+//! the range on every side with whatever the topology puts past the edges.
+//! A Moore count is then four lookups whatever the range; a von Neumann
+//! diamond takes one row segment, so two lookups, per row it spans.
+
+use super::{parallel, Board};
+use crate::rules::{LargerThanLife, LtlShape};
+
+struct SummedArea {
+    /// `(width + 1) * (height + 1)` running sums with a zero first row and
+    /// column, so `sums[y][x]` covers every cell above and left of `(x, y)`.
+    sums: Vec<u32>,
+    width: usize,
+}
+
+impl SummedArea {
+    /// Live cells in the padded rectangle `x0..x1`, `y0..y1`.
+    fn rect(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> u32 {
+        let at = |x: usize, y: usize| self.sums[y * (self.width + 1) + x];
+
+        at(x1, y1) + at(x0, y0) - at(x0, y1) - at(x1, y0)
+    }
+}
+
+impl Board {
+    pub(super) fn step_larger_than_life(&mut self, rule: &LargerThanLife, threads: usize) {
+        let r = rule.range as usize;
+        let sat = self.summed_area(r);
+        let (width, stride) = (self.width, self.stride);
+
+        let mut next = std::mem::take(&mut self.next);
+        next.clear();
+        next.resize(stride * self.height, 0);
+
+        parallel::for_each_band(&mut next, stride, threads, |rows, band| {
+            for (y, out_row) in rows.zip(band.chunks_mut(stride)) {
+                for x in 0..width {
+                    // In padded coordinates the cell sits at `(x + r, y + r)`.
+                    let mut n = match rule.shape {
+                        LtlShape::Moore => sat.rect(x, y, x + 2 * r + 1, y + 2 * r + 1),
+                        LtlShape::VonNeumann => (0..=2 * r)
+                            .map(|row| {
+                                let reach = r - row.abs_diff(r);
+                                sat.rect(x + r - reach, y + row, x + r + reach + 1, y + row + 1)
+                            })
+                            .sum(),
+                    };
+
+                    let alive = self.is_alive(x as isize, y as isize);
+                    if alive && !rule.middle {
+                        n -= 1;
+                    }
+
+                    if (alive && rule.survives(n)) || (!alive && rule.is_born(n)) {
+                        out_row[x / 64] |= 1 << (x % 64);
+                    }
+                }
+            }
+        });
+
+        if self.is_unbounded() {
+            // Only the board area is stepped on unbounded boards.
+            for y in 0..self.height {
+                for x in 0..width {
+                    let alive = next[y * stride + x / 64] >> (x % 64) & 1 == 1;
+                    self.set(x as isize, y as isize, alive);
+                }
+            }
+
+            return;
+        }
+
+        self.next = std::mem::replace(&mut self.alive, next);
+        self.activity.mark_all();
+
+        if self.states.is_some() || rule.states > 2 {
+            self.decay(rule.states);
+        }
+    }
+
+    fn summed_area(&self, padding: usize) -> SummedArea {
+        let r = padding as isize;
+        let (width, height) = (self.width + 2 * padding, self.height + 2 * padding);
+        let mut sums = vec![0; (width + 1) * (height + 1)];
+
+        for y in 0..height {
+            let mut row = 0;
+
+            for x in 0..width {
+                row += self.is_alive(x as isize - r, y as isize - r) as u32;
+                sums[(y + 1) * (width + 1) + x + 1] = sums[y * (width + 1) + x + 1] + row;
+            }
+        }
+
+        SummedArea { sums, width }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::board::Topology;
+
+    fn step_per_cell(board: &mut Board, rule: &LargerThanLife) {
+        let old = board.clone();
+        let r = rule.range as isize;
+
+        for y in 0..board.height() as isize {
+            for x in 0..board.width() as isize {
+                let mut n = 0;
+
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let inside = match rule.shape {
+                            LtlShape::Moore => true,
+                            LtlShape::VonNeumann => dx.abs() + dy.abs() <= r,
+                        };
+                        let middle = dx == 0 && dy == 0 && !rule.middle;
+
+                        if inside && !middle && old.is_alive(x + dx, y + dy) {
+                            n += 1;
+                        }
+                    }
+                }
+
+                let alive = old.is_alive(x, y);
+                board.set(
+                    x,
+                    y,
+                    if alive {
+                        rule.survives(n)
+                    } else {
+                        rule.is_born(n)
+                    },
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_per_cell_step() {
+        let mut rng = StdRng::seed_from_u64(6);
+
+        for (range, shape, middle) in [
+            (1, LtlShape::Moore, false),
+            (2, LtlShape::VonNeumann, true),
+            (3, LtlShape::Moore, true),
+            (5, LtlShape::VonNeumann, false),
+        ] {
+            let max = match shape {
+                LtlShape::Moore => (2 * range + 1) * (2 * range + 1),
+                LtlShape::VonNeumann => 2 * range * (range + 1) + 1,
+            };
+            let mut pick = || {
+                let a = rng.gen_range(0..max);
+                (a, rng.gen_range(a..=max))
+            };
+            let rule = LargerThanLife {
+                range: range as u8,
+                states: 0,
+                middle,
+                survive: pick(),
+                birth: pick(),
+                shape,
+            };
+
+            for topology in [Topology::Torus, Topology::PlaneAlive, Topology::KleinBottle] {
+                let mut fast = Board::with_topology(70, 23, topology);
+                for y in 0..23 {
+                    for x in 0..70 {
+                        fast.set(x, y, rng.gen_bool(0.5));
+                    }
+                }
+                let mut slow = fast.clone();
+
+                for generation in 0..5 {
+                    fast.step_larger_than_life(&rule, 3);
+                    step_per_cell(&mut slow, &rule);
+
+                    assert_eq!(fast.alive, slow.alive, "{rule} {topology} {generation}");
+                }
+            }
+        }
+    }
+}
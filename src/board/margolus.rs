@@ -0,0 +1,142 @@
+//! Margolus block stepping.
+//!
+//! Blocks don't overlap, so they are replaced in place. Blocks sticking out
+//! past an edge that isn't glued to another one see the outside as the
+//! topology has it, and are only stepped if that part would stay the same.
+//! Where an edge is glued to an odd sized one, the block that would wrap
+//! onto another block is left alone. Unbounded boards only step blocks with
+//! something in them, since a rule that fills empty blocks would fill the
+//! whole plane.
+
+use super::Board;
+use crate::rules::Margolus;
+
+impl Board {
+    pub(super) fn step_margolus(&mut self, rule: &Margolus) {
+        // The first generation uses blocks starting at even coordinates.
+        let shift = 1 - (self.generation % 2) as isize;
+
+        let (x0, y0, x1, y1) = if self.is_unbounded() {
+            let Some((x0, y0, x1, y1)) = self.live_bounds() else {
+                return;
+            };
+            let align = |v: isize| v - (v - shift).rem_euclid(2);
+
+            (align(x0), align(y0), x1, y1)
+        } else {
+            let x0 = if self.wrap_xy(-1, 0).is_some() {
+                shift
+            } else {
+                -shift
+            };
+            let y0 = if self.wrap_xy(0, -1).is_some() {
+                shift
+            } else {
+                -shift
+            };
+
+            (x0, y0, self.width as isize, self.height as isize)
+        };
+
+        for by in (y0..y1).step_by(2) {
+            for bx in (x0..x1).step_by(2) {
+                let Some(cells) = self.block(bx, by) else {
+                    continue;
+                };
+
+                let outside = self.topology.outside_alive();
+                let block = cells.iter().enumerate().fold(0, |b, (k, cell)| {
+                    let alive = cell.map_or(outside, |(x, y)| self.is_alive(x, y));
+                    b | (alive as u8) << k
+                });
+
+                if block == 0 && self.is_unbounded() {
+                    continue;
+                }
+
+                let next = rule.table[block as usize];
+                let outside_changes = cells
+                    .iter()
+                    .enumerate()
+                    .any(|(k, cell)| cell.is_none() && (next ^ block) >> k & 1 == 1);
+
+                if next == block || outside_changes {
+                    continue;
+                }
+
+                for (k, cell) in cells.iter().enumerate() {
+                    if let Some((x, y)) = *cell {
+                        self.set(x, y, next >> k & 1 == 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The cells of the block with its north west corner at `(x, y)`, in
+    /// the order of their bits and `None` past the edges, or `None` if the
+    /// block wraps onto another one.
+    fn block(&self, x: isize, y: isize) -> Option<[Option<(isize, isize)>; 4]> {
+        let cells = [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)];
+
+        if self.is_unbounded() {
+            return Some(cells.map(Some));
+        }
+
+        let (w, h) = (self.width as isize, self.height as isize);
+        if (x + 1 == w && w % 2 == 1 && self.wrap_xy(w, y).is_some())
+            || (y + 1 == h && h % 2 == 1 && self.wrap_xy(x, h).is_some())
+        {
+            return None;
+        }
+
+        Some(cells.map(|(x, y)| self.wrap_xy(x, y).map(|(x, y)| (x as isize, y as isize))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Topology,
+        config::Config,
+        rules::{BILLIARD_BALLS, SAND},
+    };
+
+    fn run(board: &mut Board, rule: crate::rules::Rule, generations: usize) {
+        let config = Config {
+            rule,
+            enable_heat: false,
+            ..Default::default()
+        };
+
+        for _ in 0..generations {
+            board.update(&config);
+        }
+    }
+
+    #[test]
+    fn balls_fly_and_sand_settles() {
+        for topology in [Topology::Torus, Topology::Unbounded] {
+            let mut board = Board::with_topology(12, 12, topology);
+            board.set(2, 2, true);
+            run(&mut board, BILLIARD_BALLS, 3);
+            assert_eq!(board.live_bounds(), Some((5, 5, 6, 6)), "{topology}");
+        }
+
+        let mut board = Board::with_topology(8, 8, Topology::PlaneDead);
+        for y in 0..3 {
+            for x in 1..5 {
+                board.set(x, y, true);
+            }
+        }
+        run(&mut board, SAND, 40);
+
+        let mut grains = 0;
+        board.for_each_tile_in((0, 0, 8, 8), |x, y, _| {
+            grains += 1;
+            assert!(y == 7 || board.is_alive(x, y + 1), "{x},{y} floats");
+        });
+        assert_eq!(grains, 12);
+    }
+}
@@ -0,0 +1,975 @@
+use crate::config::Config;
+use crate::rules::{automaton, AutomatonState, Neighbourhood, Params, Rule, NEIGHBOURS};
+use crate::utils::GColor;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use clipline::Clipline;
+use dirs::data_dir;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub use agents::Agent;
+pub use resize::{Anchor, ANCHORS};
+pub use topology::{Topology, TOPOLOGIES};
+
+use active::ActiveChunks;
+use bitwise::isotropic_word;
+use sparse::Sparse;
+
+mod active;
+mod agents;
+mod bitwise;
+mod generations;
+mod gray_scott;
+mod heat;
+mod lenia;
+mod ltl;
+mod margolus;
+mod parallel;
+mod resize;
+mod sand;
+mod sparse;
+mod species;
+mod stochastic;
+mod topology;
+mod wireworld;
+
+/// The alive plane is packed 64 cells to a `u64`, row by row, with every row
+/// starting on a fresh word. Heat and the states of multi-state rules live in
+/// their own planes, which are only allocated once something writes to them.
+///
+/// Unbounded boards leave the dense planes empty and keep their cells in a
+/// sparse chunk map instead. Their width and height only describe the area
+/// that gets randomized and recorded.
+#[derive(Clone)]
+pub struct Board {
+    alive: Vec<u64>,
+    /// Back buffer for the alive plane, swapped in after every step.
+    next: Vec<u64>,
+    heat: Option<Vec<u8>>,
+    states: Option<Vec<u8>>,
+    /// Cell values under continuous rules, one per cell.
+    field: Option<Vec<f32>>,
+    /// Chemicals A and B under reaction-diffusion, one pair per cell.
+    chemicals: Option<Vec<(f32, f32)>>,
+    activity: ActiveChunks,
+    sparse: Option<Sparse>,
+    topology: Topology,
+    /// The only source of randomness for randomizing and for custom rules,
+    /// so a run can be replayed from its seed.
+    rng: StdRng,
+    /// Generations stepped since the last reseed. Drives rules that change
+    /// over time.
+    generation: u64,
+    /// State of the custom rule that last stepped the board, with its index.
+    automaton: Option<(usize, Box<dyn AutomatonState>)>,
+    /// Turmite agents, stepped in order.
+    agents: Vec<Agent>,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+#[derive(Clone, Copy)]
+pub struct Tile {
+    pub alive: bool,
+    pub heat: u8,
+    /// State of the cell under multi-state rules. 0 means it is simply dead
+    /// or alive as `alive` says.
+    pub state: u8,
+}
+
+/// A plain live or dead cell.
+impl From<bool> for Tile {
+    fn from(alive: bool) -> Self {
+        Self {
+            alive,
+            heat: 0,
+            state: 0,
+        }
+    }
+}
+
+impl Tile {
+    pub fn update_heat(&mut self, config: &Config) {
+        self.heat = next_heat(self.alive, self.heat, config);
+    }
+    pub fn alive(&self) -> bool {
+        self.alive
+    }
+    pub fn heat(&self) -> u8 {
+        self.heat
+    }
+    /// Dead, cold and without any extra state.
+    pub fn is_blank(&self) -> bool {
+        !self.alive && self.heat == 0 && self.state == 0
+    }
+}
+
+fn next_heat(alive: bool, heat: u8, config: &Config) -> u8 {
+    if alive {
+        if config.soft_heat {
+            heat.saturating_add(config.soft_heat_amount)
+        } else {
+            255
+        }
+    } else {
+        heat.saturating_sub(1)
+    }
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+
+        Self {
+            alive: vec![0; stride * height],
+            next: vec![0; stride * height],
+            heat: None,
+            states: None,
+            field: None,
+            chemicals: None,
+            activity: ActiveChunks::new(stride, height),
+            sparse: None,
+            topology: Topology::Torus,
+            rng: StdRng::seed_from_u64(0),
+            generation: 0,
+            automaton: None,
+            agents: vec![],
+            width,
+            height,
+            stride,
+        }
+    }
+
+    pub fn with_topology(width: usize, height: usize, topology: Topology) -> Self {
+        let mut board = Self::new(width, height);
+        board.set_topology(topology);
+        board
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Switches topology in place. Going from unbounded to bounded keeps only
+    /// the cells inside the board's width and height.
+    pub fn set_topology(&mut self, topology: Topology) {
+        if topology == self.topology {
+            return;
+        }
+
+        // Bounded topologies share the dense planes and only differ in how
+        // coordinates past the edges are resolved.
+        if !topology.is_unbounded() && !self.is_unbounded() {
+            self.topology = topology;
+            self.activity.mark_all();
+            return;
+        }
+
+        let old = std::mem::replace(self, Self::new(self.width, self.height));
+        self.topology = topology;
+        self.agents = old.agents.clone();
+
+        if topology.is_unbounded() {
+            self.alive = vec![];
+            self.next = vec![];
+            self.sparse = Some(Sparse::default());
+        }
+
+        let everything = (isize::MIN, isize::MIN, isize::MAX, isize::MAX);
+        old.for_each_tile_in(everything, |x, y, tile| {
+            if old.is_unbounded() && !self.is_inside(x, y) {
+                return;
+            }
+
+            self.set_tile(x, y, tile);
+        });
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.sparse.is_some()
+    }
+
+    /// Restarts the random number generator and the generation count.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.generation = 0;
+        // Custom rules seed their state from the board, so it starts over.
+        self.automaton = None;
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Counts generations that were stepped somewhere else, like in HashLife.
+    pub fn add_generations(&mut self, n: u64) {
+        self.generation += n;
+    }
+
+    pub fn update(&mut self, config: &Config) {
+        // Unbounded boards don't store states, so these rules leave them as
+        // they are.
+        if self.is_unbounded() && config.rule.needs_bounds() {
+            return;
+        }
+
+        self.generation += 1;
+
+        match &config.rule {
+            Rule::Rulestring(rule) => {
+                // Leftover states from a Generations rule would block births.
+                if self.states.take().is_some() {
+                    self.activity.mark_all();
+                }
+
+                // Coupled heat is part of the rule, so it steps with it.
+                if config.couple_heat {
+                    self.step_heat_coupled(rule, &config.heat_coupling, config.threads);
+                    return;
+                }
+
+                match &mut self.sparse {
+                    Some(sparse) => sparse.step(rule, config.threads),
+                    None => {
+                        self.step_life(rule, config.threads);
+
+                        if !self.topology.is_torus() {
+                            self.step_edges(rule);
+                        }
+                    }
+                }
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Margolus(rule) => {
+                if self.states.take().is_some() {
+                    self.activity.mark_all();
+                }
+
+                self.step_margolus(rule);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Generations(rule) => {
+                self.step_generations(rule, config.threads);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::LargerThanLife(rule) => {
+                self.step_larger_than_life(rule, config.threads);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Isotropic(rule) => {
+                if self.states.take().is_some() {
+                    self.activity.mark_all();
+                }
+
+                match &mut self.sparse {
+                    Some(sparse) => {
+                        let mut rule = *rule;
+                        rule.birth[0] &= !1;
+
+                        sparse.step_with(config.threads, |neighbours, alive| {
+                            isotropic_word(&rule, neighbours, alive)
+                        });
+                    }
+                    None => {
+                        self.step_isotropic(rule, config.threads);
+
+                        if !self.topology.is_torus() {
+                            self.step_edges_with(|alive, neighbours| rule.next(alive, neighbours));
+                        }
+                    }
+                }
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Stochastic(rule) => {
+                if self.states.take().is_some() {
+                    self.activity.mark_all();
+                }
+
+                self.step_stochastic(rule);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Species(rule) => {
+                self.step_species(rule, config.threads);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Lenia(rule) => {
+                self.step_lenia(rule, config.threads);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::GrayScott(rule) => {
+                self.step_gray_scott(rule, config.threads);
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            Rule::Turmite(rule) => {
+                for _ in 0..config.agent_steps {
+                    self.step_agents(rule);
+                }
+
+                if config.enable_heat {
+                    self.update_heat(config);
+                }
+            }
+            // Fire and smoke keep their age in the heat plane, so it isn't
+            // updated as usual.
+            Rule::FallingSand => self.step_sand(),
+            Rule::Wireworld => self.step_wireworld(),
+            Rule::Custom(i) => {
+                // Rules from crates that aren't loaded right now do nothing.
+                let Some(automaton) = automaton(*i) else {
+                    return;
+                };
+
+                let mut state = match self.automaton.take() {
+                    Some((j, state)) if j == *i => state,
+                    _ => automaton.init(self),
+                };
+                let params = Params::new(
+                    &automaton.params(),
+                    config.rule_params.get(automaton.name()),
+                );
+
+                automaton.step(self, &mut *state, &params, config);
+                self.automaton = Some((*i, state));
+            }
+        }
+    }
+
+    pub fn update_heat(&mut self, config: &Config) {
+        if let Some(sparse) = &mut self.sparse {
+            sparse.update_heat(config);
+            return;
+        }
+
+        let mut heat = self
+            .heat
+            .take()
+            .unwrap_or_else(|| vec![0; self.width * self.height]);
+
+        parallel::for_each_band(&mut heat, self.width, config.threads, |rows, band| {
+            for (y, row) in rows.zip(band.chunks_mut(self.width)) {
+                for (x, heat) in row.iter_mut().enumerate() {
+                    *heat = next_heat(self.alive_at(x, y), *heat, config);
+                }
+            }
+        });
+
+        self.heat = Some(heat);
+    }
+
+    /// Resolves a coordinate through the topology, `None` if it lies outside
+    /// the board.
+    fn wrap_xy(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        self.topology.wrap(x, y, self.width, self.height)
+    }
+
+    fn xy_to_idx(&self, x: usize, y: usize) -> usize {
+        x + y * self.width
+    }
+
+    /// Word index and bit mask of an in-bounds cell in the alive plane.
+    fn bit(&self, x: usize, y: usize) -> (usize, u64) {
+        (y * self.stride + x / 64, 1 << (x % 64))
+    }
+
+    fn alive_at(&self, x: usize, y: usize) -> bool {
+        let (i, mask) = self.bit(x, y);
+
+        self.alive[i] & mask != 0
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.alive[y * self.stride..(y + 1) * self.stride]
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Tile {
+        if let Some(sparse) = &self.sparse {
+            return sparse.get(x, y);
+        }
+
+        let Some((x, y)) = self.wrap_xy(x, y) else {
+            return Tile {
+                alive: self.topology.outside_alive(),
+                heat: 0,
+                state: 0,
+            };
+        };
+        let i = self.xy_to_idx(x, y);
+
+        Tile {
+            alive: self.alive_at(x, y),
+            heat: self.heat.as_ref().map_or(0, |heat| heat[i]),
+            state: self.states.as_ref().map_or(0, |states| states[i]),
+        }
+    }
+
+    pub fn get_u(&self, x: usize, y: usize) -> Tile {
+        self.get(x as isize, y as isize)
+    }
+
+    pub fn set_tile(&mut self, x: isize, y: isize, tile: Tile) {
+        self.set(x, y, tile.alive);
+        self.set_heat(x, y, tile.heat);
+        self.set_state(x, y, tile.state);
+    }
+
+    pub fn set_tile_u(&mut self, x: usize, y: usize, tile: Tile) {
+        self.set_tile(x as isize, y as isize, tile);
+    }
+
+    pub fn set_heat(&mut self, x: isize, y: isize, heat: u8) {
+        if let Some(sparse) = &mut self.sparse {
+            sparse.set_heat(x, y, heat);
+            return;
+        }
+
+        if heat == 0 && self.heat.is_none() {
+            return;
+        }
+
+        let Some((x, y)) = self.wrap_xy(x, y) else {
+            return;
+        };
+        let i = self.xy_to_idx(x, y);
+        let len = self.width * self.height;
+
+        self.heat.get_or_insert_with(|| vec![0; len])[i] = heat;
+    }
+
+    /// Sets the multi-state rule state of a cell. Unbounded boards don't keep
+    /// states.
+    pub fn set_state(&mut self, x: isize, y: isize, state: u8) {
+        if self.is_unbounded() || (state == 0 && self.states.is_none()) {
+            return;
+        }
+
+        let Some((x, y)) = self.wrap_xy(x, y) else {
+            return;
+        };
+        let i = self.xy_to_idx(x, y);
+        let len = self.width * self.height;
+        let states = self.states.get_or_insert_with(|| vec![0; len]);
+
+        if states[i] != state {
+            states[i] = state;
+            self.activity.mark(x, y);
+        }
+    }
+
+    pub fn is_alive(&self, x: isize, y: isize) -> bool {
+        if let Some(sparse) = &self.sparse {
+            return sparse.get(x, y).alive;
+        }
+
+        self.wrap_xy(x, y)
+            .map_or(self.topology.outside_alive(), |(x, y)| self.alive_at(x, y))
+    }
+
+    pub fn count_neighbors(&self, x: isize, y: isize, neighbourhood: Neighbourhood) -> usize {
+        NEIGHBOURS
+            .iter()
+            .enumerate()
+            .filter(|(k, (dx, dy))| {
+                neighbourhood.mask() >> k & 1 == 1 && self.is_alive(x + dx, y + dy)
+            })
+            .count()
+    }
+
+    pub fn is_inside(&self, x: isize, y: isize) -> bool {
+        self.is_unbounded()
+            || (x >= 0 && x < self.width as isize && y >= 0 && y < self.height as isize)
+    }
+
+    /// The cells of a line, clipped to the board unless it is unbounded.
+    pub fn line(&self, x0: isize, y0: isize, x1: isize, y1: isize) -> Option<Clipline> {
+        let clip = if self.is_unbounded() {
+            ((x0.min(x1), y0.min(y1)), (x0.max(x1), y0.max(y1)))
+        } else {
+            ((0, 0), (self.width as isize - 1, self.height as isize - 1))
+        };
+
+        Clipline::new(((x0, y0), (x1, y1)), clip)
+    }
+
+    pub fn set_line(
+        &mut self,
+        x0: isize,
+        y0: isize,
+        x1: isize,
+        y1: isize,
+        to: impl Into<Tile>,
+    ) -> Option<()> {
+        let to = to.into();
+        for (x, y) in self.line(x0, y0, x1, y1)? {
+            self.paint(x, y, to);
+        }
+        Some(())
+    }
+
+    pub fn draw(&mut self, x: isize, y: isize, radius: usize, to: impl Into<Tile>) {
+        let to = to.into();
+        let radius = radius as isize;
+        for dy in y - radius..=y + radius {
+            for dx in x - radius..=x + radius {
+                if self.is_inside(x, y) {
+                    let x2 = (dx - x).abs();
+                    let y2 = (dy - y).abs();
+                    if x2.pow(2) + y2.pow(2) <= radius.pow(2) {
+                        self.paint(dx, dy, to);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn draw_line(
+        &mut self,
+        x0: isize,
+        y0: isize,
+        x1: isize,
+        y1: isize,
+        radius: usize,
+        to: impl Into<Tile>,
+    ) -> Option<()> {
+        let to = to.into();
+        for (x, y) in self.line(x0, y0, x1, y1)? {
+            self.draw(x, y, radius, to);
+        }
+        Some(())
+    }
+
+    /// Makes a cell alive or dead, dropping any multi-state rule state.
+    pub fn set(&mut self, x: isize, y: isize, to: bool) {
+        if let Some(sparse) = &mut self.sparse {
+            sparse.set(x, y, to);
+            return;
+        }
+
+        let Some((x, y)) = self.wrap_xy(x, y) else {
+            return;
+        };
+
+        self.set_bit(x, y, to);
+
+        let i = self.xy_to_idx(x, y);
+        if let Some(state) = self.states.as_mut().map(|states| &mut states[i]) {
+            if *state != 0 {
+                *state = 0;
+                self.activity.mark(x, y);
+            }
+        }
+    }
+
+    /// Gives a cell the alive bit and state of `tile`, keeping its heat.
+    pub fn paint(&mut self, x: isize, y: isize, tile: Tile) {
+        self.set(x, y, tile.alive);
+        self.set_state(x, y, tile.state);
+    }
+
+    /// Flips an in-bounds cell in the alive plane only.
+    fn set_bit(&mut self, x: usize, y: usize, to: bool) {
+        let (i, mask) = self.bit(x, y);
+
+        if (self.alive[i] & mask != 0) != to {
+            self.alive[i] ^= mask;
+            self.activity.mark(x, y);
+        }
+    }
+
+    pub fn set_u(&mut self, x: usize, y: usize, to: bool) {
+        self.set(x as isize, y as isize, to);
+    }
+
+    pub fn set_line_u(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, to: bool) {
+        self.set_line(x0 as isize, y0 as isize, x1 as isize, y1 as isize, to);
+    }
+
+    pub fn randomize(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let alive = self.rng.gen_bool(0.5);
+                self.set_u(x, y, alive);
+            }
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.set_heat(x as isize, y as isize, 255);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.alive.fill(0);
+        self.heat = None;
+        self.states = None;
+        self.field = None;
+        self.chemicals = None;
+        self.agents.clear();
+        self.activity.mark_all();
+
+        if let Some(sparse) = &mut self.sparse {
+            sparse.clear();
+        }
+    }
+
+    /// Kills every cell but leaves the heat plane alone.
+    pub fn clear_alive(&mut self) {
+        self.alive.fill(0);
+        self.states = None;
+        self.field = None;
+        self.chemicals = None;
+        self.activity.mark_all();
+
+        if let Some(sparse) = &mut self.sparse {
+            sparse.clear_alive();
+        }
+    }
+
+    /// Calls `f` for every cell inside the rectangle that isn't blank
+    /// `(x0, y0, x1, y1)`, upper bounds exclusive.
+    pub fn for_each_tile_in(
+        &self,
+        (x0, y0, x1, y1): (isize, isize, isize, isize),
+        mut f: impl FnMut(isize, isize, Tile),
+    ) {
+        if let Some(sparse) = &self.sparse {
+            sparse.for_each_tile_in((x0, y0, x1, y1), f);
+            return;
+        }
+
+        for y in y0.max(0)..y1.min(self.height as isize) {
+            for x in x0.max(0)..x1.min(self.width as isize) {
+                let tile = self.get(x, y);
+
+                if !tile.is_blank() {
+                    f(x, y, tile);
+                }
+            }
+        }
+    }
+
+    /// Calls `f` with the color of every cell in `rect` that isn't plain dead
+    /// under the current rule. Custom rules color every cell themselves, and
+    /// continuous and reaction-diffusion ones go by the cell's value or B.
+    pub fn for_each_colored_tile_in(
+        &self,
+        rect: (isize, isize, isize, isize),
+        config: &Config,
+        mut f: impl FnMut(isize, isize, GColor),
+    ) {
+        if matches!(config.rule, Rule::Lenia(_) | Rule::GrayScott(_)) && !self.is_unbounded() {
+            let (x0, y0, x1, y1) = rect;
+            for y in y0.max(0)..y1.min(self.height as isize) {
+                for x in x0.max(0)..x1.min(self.width as isize) {
+                    let (x_u, y_u) = (x as usize, y as usize);
+                    // B rarely gets past a half.
+                    let value = match config.rule {
+                        Rule::GrayScott(_) => (self.concentration(x_u, y_u) * 2.).min(1.),
+                        _ => self.value(x_u, y_u),
+                    };
+
+                    if let Some(color) = config.value_color(value) {
+                        f(x, y, color);
+                    }
+                }
+            }
+            return;
+        }
+
+        let custom = match (config.rule, &self.automaton) {
+            (Rule::Custom(i), Some((j, state))) if i == *j && !self.is_unbounded() => {
+                automaton(i).map(|automaton| (automaton, state))
+            }
+            _ => None,
+        };
+
+        let Some((automaton, state)) = custom else {
+            self.for_each_tile_in(rect, |x, y, tile| {
+                if let Some(color) = config.tile_color(tile) {
+                    f(x, y, color);
+                }
+            });
+            return;
+        };
+
+        let (x0, y0, x1, y1) = rect;
+        for y in y0.max(0)..y1.min(self.height as isize) {
+            for x in x0.max(0)..x1.min(self.width as isize) {
+                if let Some(color) = automaton.color(&**state, x, y, self.get(x, y), config) {
+                    f(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Smallest rectangle holding every live cell, as `(x0, y0, x1, y1)` with
+    /// exclusive upper bounds.
+    pub fn live_bounds(&self) -> Option<(isize, isize, isize, isize)> {
+        if let Some(sparse) = &self.sparse {
+            return sparse.bounds();
+        }
+
+        let mut bounds: Option<(isize, isize, isize, isize)> = None;
+        let everything = (0, 0, self.width as isize, self.height as isize);
+
+        self.for_each_tile_in(everything, |x, y, tile| {
+            if tile.alive {
+                bounds = Some(match bounds {
+                    None => (x, y, x + 1, y + 1),
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+                });
+            }
+        });
+
+        bounds
+    }
+
+    /// Chunks stepped in the last generation and the total, for dense
+    /// boards.
+    pub fn active_chunks(&self) -> Option<(usize, usize)> {
+        (!self.is_unbounded()).then(|| self.activity.count())
+    }
+
+    /// Number of stored chunks on an unbounded board.
+    pub fn chunk_count(&self) -> Option<usize> {
+        self.sparse.as_ref().map(Sparse::chunk_count)
+    }
+
+    pub fn debug_print(&self, iter_count: u64) {
+        println!("\n\n---\n");
+        println!("{}x{} // {}", self.width, self.height, iter_count);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = self.get(x as isize, y as isize);
+                print!("{}", if tile.alive { "#" } else { " " });
+            }
+            println!();
+        }
+        println!("\n---\n\n");
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn saves_dir() -> String {
+        format!("{}/gol2/saves", data_dir().unwrap().display())
+    }
+}
+
+impl Serialize for Board {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let width = self.width();
+        let height = self.height();
+
+        // Unbounded boards store the rectangle around their live cells, which
+        // may lie anywhere, instead of the board area.
+        let (x0, y0, x1, y1) = if self.is_unbounded() {
+            self.live_bounds().unwrap_or_default()
+        } else {
+            (0, 0, width as isize, height as isize)
+        };
+
+        let bytes = bools_to_u8s((y0..y1).flat_map(|y| (x0..x1).map(move |x| self.is_alive(x, y))));
+        let base64 = BASE64_STANDARD.encode(&bytes);
+        // One byte per cell, in the same order as `cells`.
+        let states = self
+            .states
+            .as_ref()
+            .map(|states| BASE64_STANDARD.encode(states));
+        // Little endian floats, one per cell of the board area.
+        let field = self.field.as_ref().map(|field| {
+            let bytes: Vec<u8> = field.iter().flat_map(|v| v.to_le_bytes()).collect();
+            BASE64_STANDARD.encode(bytes)
+        });
+
+        let mut state = serializer.serialize_struct("Board", 9)?;
+        state.serialize_field("width", &width)?;
+        state.serialize_field("height", &height)?;
+        state.serialize_field("topology", &self.topology())?;
+        state.serialize_field("cells_rect", &(x0, y0, x1 - x0, y1 - y0))?;
+        state.serialize_field("cells", &base64)?;
+        state.serialize_field("states", &states)?;
+        state.serialize_field("agents", &self.agents)?;
+        state.serialize_field("field", &field)?;
+        state.end()
+    }
+}
+
+fn bools_to_u8s<I>(bool_iter: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = bool>,
+{
+    let mut result = Vec::new();
+    let mut current_byte = 0;
+    let mut bits_set = 0;
+
+    for bool_value in bool_iter.into_iter() {
+        current_byte |= (bool_value as u8) << bits_set;
+        bits_set += 1;
+
+        if bits_set == 8 {
+            result.push(current_byte);
+            current_byte = 0;
+            bits_set = 0;
+        }
+    }
+
+    if bits_set > 0 {
+        result.push(current_byte);
+    }
+
+    result
+}
+
+#[test]
+fn test_bools_to_u8s() {
+    let bools = vec![true, false, true, true, false, false, true, true];
+    let result = bools_to_u8s(bools);
+    assert_eq!(result, vec![0b11001101]);
+
+    let bools = vec![true; 16];
+    let result = bools_to_u8s(bools);
+    assert_eq!(result, vec![0xFF, 0xFF]);
+}
+
+#[test]
+fn seeded_runs_are_identical() {
+    use crate::rules::{FALLING_STARS, NOISE, PERLIN_NOISE, SPACE, WORLEY_LINES};
+
+    for rule in [FALLING_STARS, NOISE, WORLEY_LINES, SPACE, PERLIN_NOISE] {
+        let config = Config {
+            rule,
+            ..Config::default()
+        };
+        let run = || {
+            let mut board = Board::new(60, 40);
+            board.reseed(42);
+            board.randomize();
+
+            for _ in 0..20 {
+                board.update(&config);
+            }
+
+            board
+        };
+
+        let (a, b) = (run(), run());
+        assert_eq!(a.alive, b.alive);
+        assert_eq!(a.heat, b.heat);
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct BoardData {
+            width: usize,
+            height: usize,
+            #[serde(default)]
+            topology: Topology,
+            /// `(x, y, width, height)` of the area covered by `cells`. Older
+            /// saves leave it out and cover the whole board.
+            #[serde(default)]
+            cells_rect: Option<(isize, isize, usize, usize)>,
+            cells: String,
+            #[serde(default)]
+            states: Option<String>,
+            #[serde(default)]
+            agents: Vec<Agent>,
+            #[serde(default)]
+            field: Option<String>,
+        }
+
+        let data = BoardData::deserialize(deserializer)?;
+
+        let decoded_cells = BASE64_STANDARD
+            .decode(data.cells.as_bytes())
+            .map_err(serde::de::Error::custom)?;
+
+        let mut board = Board::with_topology(data.width, data.height, data.topology);
+        board.agents = data.agents;
+        let (x0, y0, width, height) = data.cells_rect.unwrap_or((0, 0, data.width, data.height));
+        let bits = decoded_cells
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| byte & (1 << i) != 0));
+
+        for (i, alive) in bits.take(width * height).enumerate() {
+            if alive {
+                board.set(x0 + (i % width) as isize, y0 + (i / width) as isize, true);
+            }
+        }
+
+        if let Some(states) = data.states {
+            let states = BASE64_STANDARD
+                .decode(states.as_bytes())
+                .map_err(serde::de::Error::custom)?;
+
+            for (i, &state) in states.iter().take(width * height).enumerate() {
+                board.set_state(x0 + (i % width) as isize, y0 + (i / width) as isize, state);
+            }
+        }
+
+        if let Some(field) = data.field {
+            let bytes = BASE64_STANDARD
+                .decode(field.as_bytes())
+                .map_err(serde::de::Error::custom)?;
+
+            if bytes.len() != data.width * data.height * 4 || board.is_unbounded() {
+                return Err(serde::de::Error::custom("field doesn't match the board"));
+            }
+            board.field = Some(
+                bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+            );
+        }
+
+        Ok(board)
+    }
+}
@@ -0,0 +1,68 @@
+//! Row-band parallelism for generation steps.
+//!
+//! The output buffer is cut into bands of whole rows which are handed to a
+//! worker pool that is kept between steps, so stepping doesn't start new
+//! threads every generation. Every band is computed from the same read-only
+//! source, so the result never depends on how the bands were scheduled.
+
+use std::{
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+/// Bands smaller than this are not worth handing to another thread.
+const MIN_BAND_ROWS: usize = 16;
+
+/// The worker pool with its thread count, rebuilt when the count changes.
+static POOL: Mutex<Option<(usize, Arc<ThreadPool>)>> = Mutex::new(None);
+
+fn pool(threads: usize) -> Arc<ThreadPool> {
+    let mut pool = POOL.lock().unwrap();
+
+    match &*pool {
+        Some((n, workers)) if *n == threads => workers.clone(),
+        _ => {
+            let workers = Arc::new(
+                ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .expect("failed to start the worker threads"),
+            );
+            *pool = Some((threads, workers.clone()));
+            workers
+        }
+    }
+}
+
+/// Runs `step` over every band of `out`, where a row is `row_len` elements
+/// long. `step` receives the rows covered by the band and the band itself.
+pub fn for_each_band<T, F>(out: &mut [T], row_len: usize, threads: usize, step: F)
+where
+    T: Send,
+    F: Fn(Range<usize>, &mut [T]) + Sync,
+{
+    if row_len == 0 {
+        return;
+    }
+
+    let rows = out.len() / row_len;
+
+    if threads <= 1 || rows < MIN_BAND_ROWS * 2 {
+        step(0..rows, out);
+        return;
+    }
+
+    // A few bands per worker keeps them busy when some bands finish early.
+    let band_rows = rows.div_ceil(threads * 4).max(MIN_BAND_ROWS);
+
+    pool(threads).install(|| {
+        out.par_chunks_mut(band_rows * row_len)
+            .enumerate()
+            .for_each(|(i, band)| {
+                let start = i * band_rows;
+                step(start..start + band.len() / row_len, band);
+            });
+    });
+}
@@ -0,0 +1,222 @@
+use strum::Display;
+
+use super::{Agent, Board};
+
+/// The part of the board that stays in place when it is resized.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Display)]
+pub enum Anchor {
+    #[strum(to_string = "Top left")]
+    TopLeft,
+    Top,
+    #[strum(to_string = "Top right")]
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    #[strum(to_string = "Bottom left")]
+    BottomLeft,
+    Bottom,
+    #[strum(to_string = "Bottom right")]
+    BottomRight,
+}
+
+pub const ANCHORS: &[Anchor] = &[
+    Anchor::TopLeft,
+    Anchor::Top,
+    Anchor::TopRight,
+    Anchor::Left,
+    Anchor::Center,
+    Anchor::Right,
+    Anchor::BottomLeft,
+    Anchor::Bottom,
+    Anchor::BottomRight,
+];
+
+impl Anchor {
+    /// Where the anchor sits along each axis: 0 at the start, 1 in the middle
+    /// and 2 at the end.
+    fn thirds(self) -> (isize, isize) {
+        match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2),
+        }
+    }
+}
+
+impl Board {
+    /// Changes the board size, keeping cells and heat. The anchor decides
+    /// which part of the old board lines up with the same part of the new
+    /// one; cells that no longer fit are dropped.
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        if self.is_unbounded() {
+            // The cells aren't tied to the board area, which only decides what
+            // gets randomized and recorded.
+            self.width = width;
+            self.height = height;
+            return;
+        }
+
+        let (ax, ay) = anchor.thirds();
+        let dx = (width as isize - self.width as isize) * ax / 2;
+        let dy = (height as isize - self.height as isize) * ay / 2;
+
+        let everything = (0, 0, self.width as isize, self.height as isize);
+        self.copy_into(width, height, everything, (dx, dy));
+    }
+
+    /// Shrinks the board to the rectangle `(x0, y0, x1, y1)`, upper bounds
+    /// exclusive, moving its top left corner to the origin.
+    pub fn crop(&mut self, rect: (isize, isize, isize, isize)) {
+        let (x0, y0, x1, y1) = rect;
+        let (width, height) = ((x1 - x0).max(1) as usize, (y1 - y0).max(1) as usize);
+
+        self.copy_into(width, height, rect, (-x0, -y0));
+    }
+
+    /// Replaces the board with a `width` x `height` one holding the tiles of
+    /// `rect`, shifted by `offset`. Topology, RNG and generation carry over.
+    fn copy_into(
+        &mut self,
+        width: usize,
+        height: usize,
+        rect: (isize, isize, isize, isize),
+        (dx, dy): (isize, isize),
+    ) {
+        let mut board = Board::with_topology(width, height, self.topology);
+        board.rng = self.rng.clone();
+        board.generation = self.generation;
+        board.agents = self
+            .agents
+            .iter()
+            .filter(|agent| {
+                let (x0, y0, x1, y1) = rect;
+                (x0..x1).contains(&agent.x) && (y0..y1).contains(&agent.y)
+            })
+            .map(|agent| Agent {
+                x: agent.x + dx,
+                y: agent.y + dy,
+                ..*agent
+            })
+            .filter(|agent| board.is_inside(agent.x, agent.y))
+            .collect();
+
+        self.for_each_tile_in(rect, |x, y, tile| {
+            let (x, y) = (x + dx, y + dy);
+
+            if board.is_inside(x, y) {
+                board.set_tile(x, y, tile);
+            }
+        });
+
+        board.field = self
+            .field
+            .as_ref()
+            .map(|field| self.copy_plane(field, 0., &board, rect, (dx, dy)));
+        board.chemicals = self
+            .chemicals
+            .as_ref()
+            .map(|chemicals| self.copy_plane(chemicals, (1., 0.), &board, rect, (dx, dy)));
+
+        *self = board;
+    }
+
+    /// Copies a plane covering the board area onto `board` the way
+    /// `copy_into` copies tiles, with `empty` wherever nothing was copied.
+    fn copy_plane<T: Copy>(
+        &self,
+        plane: &[T],
+        empty: T,
+        board: &Board,
+        (x0, y0, x1, y1): (isize, isize, isize, isize),
+        (dx, dy): (isize, isize),
+    ) -> Vec<T> {
+        let mut copy = vec![empty; board.width * board.height];
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if self.is_inside(x, y) && board.is_inside(x + dx, y + dy) {
+                    let (nx, ny) = ((x + dx) as usize, (y + dy) as usize);
+                    copy[ny * board.width + nx] = plane[y as usize * self.width + x as usize];
+                }
+            }
+        }
+
+        copy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_keeps_cells_at_the_anchor() {
+        for (anchor, expected) in [
+            (Anchor::TopLeft, (1, 2)),
+            (Anchor::Center, (6, 7)),
+            (Anchor::BottomRight, (11, 12)),
+            (Anchor::Right, (11, 7)),
+        ] {
+            let mut board = Board::new(10, 10);
+            board.set(1, 2, true);
+            board.set_heat(3, 3, 100);
+
+            board.resize(20, 20, anchor);
+
+            assert_eq!((board.width(), board.height()), (20, 20));
+            assert_eq!(
+                board.live_bounds(),
+                Some((expected.0, expected.1, expected.0 + 1, expected.1 + 1)),
+                "{anchor}",
+            );
+            assert_eq!(board.get(expected.0 + 2, expected.1 + 1).heat, 100);
+        }
+    }
+
+    #[test]
+    fn crop_moves_the_rectangle_to_the_origin() {
+        let mut board = Board::new(30, 30);
+        board.set(10, 12, true);
+        board.set(14, 13, true);
+        board.set(2, 2, true);
+
+        board.crop((10, 12, 15, 14));
+
+        assert_eq!((board.width(), board.height()), (5, 2));
+        assert!(board.is_alive(0, 0) && board.is_alive(4, 1));
+        assert_eq!(board.live_bounds(), Some((0, 0, 5, 2)));
+    }
+
+    #[test]
+    fn continuous_values_survive_crops_and_growth() {
+        let mut board = Board::new(10, 10);
+        let mut field = vec![0.; 100];
+        field[3 * 10 + 4] = 0.3;
+        field[5 * 10 + 6] = 0.7;
+        board.field = Some(field);
+        let mut chemicals = vec![(1., 0.); 100];
+        chemicals[3 * 10 + 4] = (0.6, 0.2);
+        board.chemicals = Some(chemicals);
+
+        board.resize(20, 20, Anchor::TopLeft);
+        assert_eq!(board.value(4, 3), 0.3);
+        assert_eq!(board.value(6, 5), 0.7);
+        assert_eq!(board.value(15, 15), 0.);
+        assert_eq!(board.chemicals.as_ref().unwrap()[3 * 20 + 4], (0.6, 0.2));
+        assert_eq!(board.chemicals.as_ref().unwrap()[15 * 20 + 15], (1., 0.));
+
+        board.crop((4, 3, 8, 7));
+        assert_eq!((board.width(), board.height()), (4, 4));
+        assert_eq!(board.value(0, 0), 0.3);
+        assert_eq!(board.value(2, 2), 0.7);
+        assert_eq!(board.concentration(0, 0), 0.2);
+    }
+}
@@ -0,0 +1,156 @@
+//! Falling sand stepping.
+//!
+//! Cells are visited from the bottom row up, in a direction that flips every
+//! generation so nothing drifts to one side, and each cell moves at most
+//! once per step. Sand falls straight down or slides diagonally, water also
+//! spreads sideways, and both sink through anything lighter. Fire flickers
+//! upwards and burns out into smoke, or is put out by water next to it.
+//! Smoke rises and thins out.
+//!
+//! Materials live in the state plane, which unbounded boards don't keep, so
+//! nothing moves there.
+
+use rand::Rng;
+
+use super::Board;
+use crate::rules::Material;
+
+/// Generations before fire may burn out or smoke clear.
+const FIRE_LIFE: u8 = 20;
+const SMOKE_LIFE: u8 = 40;
+
+impl Board {
+    pub(super) fn step_sand(&mut self) {
+        let (width, height) = (self.width, self.height);
+        let mut cells: Vec<Material> = (0..width * height)
+            .map(|i| Material::of(self.get_u(i % width, i / width)))
+            .collect();
+        let mut ages = self.heat.take().unwrap_or_else(|| vec![0; width * height]);
+        let mut moved = vec![false; width * height];
+        let flip = self.generation % 2 == 1;
+
+        for y in (0..height).rev() {
+            for i in 0..width {
+                let x = if flip { width - 1 - i } else { i };
+                let at = y * width + x;
+                let material = cells[at];
+
+                if moved[at] || matches!(material, Material::Empty | Material::Stone) {
+                    continue;
+                }
+
+                // Only fire and smoke age.
+                ages[at] = match material {
+                    Material::Fire | Material::Smoke => ages[at].saturating_add(1),
+                    _ => 0,
+                };
+                let side: isize = if self.rng.gen_bool(0.5) { 1 } else { -1 };
+
+                let moves: &[(isize, isize)] = match material {
+                    Material::Sand => &[(0, 1), (side, 1), (-side, 1)],
+                    Material::Water => &[(0, 1), (side, 1), (-side, 1), (side, 0), (-side, 0)],
+                    Material::Fire => {
+                        let doused = [(0, -1), (-1, 0), (1, 0), (0, 1)].iter().any(|&(dx, dy)| {
+                            self.sand_neighbour(x, y, dx, dy)
+                                .is_some_and(|j| cells[j] == Material::Water)
+                        });
+
+                        if doused || (ages[at] > FIRE_LIFE && self.rng.gen_ratio(1, 8)) {
+                            cells[at] = Material::Smoke;
+                            ages[at] = 0;
+                            continue;
+                        }
+
+                        &[(side, -1), (0, -1)]
+                    }
+                    Material::Smoke => {
+                        if ages[at] > SMOKE_LIFE && self.rng.gen_ratio(1, 16) {
+                            cells[at] = Material::Empty;
+                            ages[at] = 0;
+                            continue;
+                        }
+
+                        &[(0, -1), (side, -1), (-side, -1), (side, 0)]
+                    }
+                    Material::Empty | Material::Stone => unreachable!(),
+                };
+
+                let sinks = material.density() > 1;
+                let target = moves.iter().find_map(|&(dx, dy)| {
+                    let j = self.sand_neighbour(x, y, dx, dy)?;
+                    let free = if sinks {
+                        cells[j].density() < material.density()
+                    } else {
+                        cells[j] == Material::Empty
+                    };
+
+                    (free && !moved[j]).then_some(j)
+                });
+
+                if let Some(j) = target {
+                    cells.swap(at, j);
+                    ages.swap(at, j);
+                    moved[j] = true;
+                }
+            }
+        }
+
+        for (i, (&material, age)) in cells.iter().zip(&mut ages).enumerate() {
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+
+            if material == Material::Empty {
+                *age = 0;
+            }
+            self.paint(x, y, material.tile());
+        }
+
+        self.heat = Some(ages);
+    }
+
+    /// Index of the cell `(dx, dy)` away from an in-bounds cell, if there is
+    /// one.
+    fn sand_neighbour(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<usize> {
+        let (x, y) = self.wrap_xy(x as isize + dx, y as isize + dy)?;
+
+        Some(self.xy_to_idx(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::Topology, config::Config, rules::FALLING_SAND};
+
+    #[test]
+    fn sand_sinks_through_water() {
+        let config = Config {
+            rule: FALLING_SAND,
+            ..Default::default()
+        };
+        let mut board = Board::with_topology(6, 8, Topology::PlaneDead);
+        for x in 0..6 {
+            board.paint(x, 7, Material::Stone.tile());
+            board.paint(x, 6, Material::Water.tile());
+        }
+        board.paint(2, 0, Material::Sand.tile());
+
+        for _ in 0..20 {
+            board.update(&config);
+        }
+
+        let count = |board: &Board, material| {
+            let mut n = 0;
+            board.for_each_tile_in((0, 0, 6, 8), |_, _, tile| {
+                n += (Material::of(tile) == material) as usize;
+            });
+            n
+        };
+
+        assert_eq!(Material::of(board.get(2, 6)), Material::Sand);
+        assert_eq!(count(&board, Material::Water), 6);
+        assert_eq!(count(&board, Material::Stone), 6);
+        for x in 0..6 {
+            assert_eq!(Material::of(board.get(x, 7)), Material::Stone);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! Storage for unbounded boards.
+//!
+//! Only 64x64 chunks holding live cells or fading heat are kept, keyed by
+//! chunk coordinates. A step visits every populated chunk and its eight
+//! neighbours, so patterns can grow into empty space in any direction.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{bitwise::life_word, next_heat, parallel, Tile};
+use crate::{config::Config, rules::Rulestring};
+
+pub(super) const CHUNK: isize = 64;
+
+const CHUNK_CELLS: usize = (CHUNK * CHUNK) as usize;
+const EMPTY_ROWS: [u64; CHUNK as usize] = [0; CHUNK as usize];
+
+type ChunkPos = (isize, isize);
+
+#[derive(Clone)]
+struct Chunk {
+    /// Bit `x` of `rows[y]` is the cell at `(x, y)` within the chunk.
+    rows: [u64; CHUNK as usize],
+    heat: Option<Box<[u8; CHUNK_CELLS]>>,
+}
+
+impl Chunk {
+    fn new() -> Self {
+        Self {
+            rows: EMPTY_ROWS,
+            heat: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rows == EMPTY_ROWS && self.heat.as_ref().is_none_or(|h| h.iter().all(|&h| h == 0))
+    }
+}
+
+#[derive(Clone, Default)]
+pub(super) struct Sparse {
+    chunks: HashMap<ChunkPos, Chunk>,
+}
+
+/// Chunk position and in-chunk coordinates of a cell.
+fn split(x: isize, y: isize) -> (ChunkPos, usize, usize) {
+    (
+        (x.div_euclid(CHUNK), y.div_euclid(CHUNK)),
+        x.rem_euclid(CHUNK) as usize,
+        y.rem_euclid(CHUNK) as usize,
+    )
+}
+
+impl Sparse {
+    pub fn get(&self, x: isize, y: isize) -> Tile {
+        let (pos, cx, cy) = split(x, y);
+
+        self.chunks.get(&pos).map_or(
+            Tile {
+                alive: false,
+                heat: 0,
+                state: 0,
+            },
+            |chunk| Tile {
+                alive: chunk.rows[cy] >> cx & 1 == 1,
+                heat: chunk
+                    .heat
+                    .as_ref()
+                    .map_or(0, |heat| heat[cy * CHUNK as usize + cx]),
+                state: 0,
+            },
+        )
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, to: bool) {
+        let (pos, cx, cy) = split(x, y);
+
+        if to {
+            self.chunks.entry(pos).or_insert_with(Chunk::new).rows[cy] |= 1 << cx;
+        } else if let Some(chunk) = self.chunks.get_mut(&pos) {
+            chunk.rows[cy] &= !(1 << cx);
+        }
+    }
+
+    pub fn set_heat(&mut self, x: isize, y: isize, heat: u8) {
+        let (pos, cx, cy) = split(x, y);
+
+        if heat == 0 && !self.chunks.contains_key(&pos) {
+            return;
+        }
+
+        let chunk = self.chunks.entry(pos).or_insert_with(Chunk::new);
+        chunk.heat.get_or_insert_with(|| Box::new([0; CHUNK_CELLS]))[cy * CHUNK as usize + cx] =
+            heat;
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    pub fn clear_alive(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            chunk.rows = EMPTY_ROWS;
+        }
+
+        self.prune();
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Smallest rectangle holding every live cell, as `(x0, y0, x1, y1)` with
+    /// exclusive upper bounds.
+    pub fn bounds(&self) -> Option<(isize, isize, isize, isize)> {
+        let mut bounds: Option<(isize, isize, isize, isize)> = None;
+
+        for (&(chunk_x, chunk_y), chunk) in &self.chunks {
+            for (cy, &row) in chunk.rows.iter().enumerate() {
+                if row == 0 {
+                    continue;
+                }
+
+                let y = chunk_y * CHUNK + cy as isize;
+                let x0 = chunk_x * CHUNK + row.trailing_zeros() as isize;
+                let x1 = chunk_x * CHUNK + (64 - row.leading_zeros()) as isize;
+
+                bounds = Some(match bounds {
+                    None => (x0, y, x1, y + 1),
+                    Some((bx0, by0, bx1, by1)) => {
+                        (bx0.min(x0), by0.min(y), bx1.max(x1), by1.max(y + 1))
+                    }
+                });
+            }
+        }
+
+        bounds
+    }
+
+    /// Calls `f` for every live or still warm cell inside the rectangle.
+    pub fn for_each_tile_in(
+        &self,
+        (x0, y0, x1, y1): (isize, isize, isize, isize),
+        mut f: impl FnMut(isize, isize, Tile),
+    ) {
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        let (first, ..) = split(x0, y0);
+        let (last, ..) = split(x1 - 1, y1 - 1);
+
+        for chunk_y in first.1..=last.1 {
+            for chunk_x in first.0..=last.0 {
+                let Some(chunk) = self.chunks.get(&(chunk_x, chunk_y)) else {
+                    continue;
+                };
+
+                let (base_x, base_y) = (chunk_x * CHUNK, chunk_y * CHUNK);
+
+                for cy in (y0 - base_y).max(0)..(y1 - base_y).min(CHUNK) {
+                    for cx in (x0 - base_x).max(0)..(x1 - base_x).min(CHUNK) {
+                        let i = (cy * CHUNK + cx) as usize;
+                        let tile = Tile {
+                            alive: chunk.rows[cy as usize] >> cx & 1 == 1,
+                            heat: chunk.heat.as_ref().map_or(0, |heat| heat[i]),
+                            state: 0,
+                        };
+
+                        if !tile.is_blank() {
+                            f(base_x + cx, base_y + cy, tile);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Steps every populated chunk and its neighbours. Births on zero
+    /// neighbours are ignored, as B0 would fill the whole infinite plane.
+    pub fn step(&mut self, rule: &Rulestring, threads: usize) {
+        let mut rule = *rule;
+        rule.spawn[0] = false;
+
+        self.step_with(threads, |neighbours, alive| {
+            life_word(&rule, neighbours, alive)
+        });
+    }
+
+    /// Like [`Sparse::step`], with `word` giving the next state of 64 cells.
+    /// It must not give birth to cells with no neighbours.
+    pub fn step_with(&mut self, threads: usize, word: impl Fn([u64; 8], u64) -> u64 + Sync) {
+        let mut positions = HashSet::new();
+        for (&(x, y), chunk) in &self.chunks {
+            positions.insert((x, y));
+
+            if chunk.rows != EMPTY_ROWS {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        positions.insert((x + dx, y + dy));
+                    }
+                }
+            }
+        }
+
+        let positions: Vec<ChunkPos> = positions.into_iter().collect();
+        let mut next = vec![EMPTY_ROWS; positions.len()];
+
+        parallel::for_each_band(&mut next, 1, threads, |range, band| {
+            for (i, rows) in range.zip(band) {
+                *rows = self.step_chunk(positions[i], &word);
+            }
+        });
+
+        let mut old = std::mem::take(&mut self.chunks);
+
+        for (pos, rows) in positions.into_iter().zip(next) {
+            let chunk = Chunk {
+                rows,
+                heat: old.remove(&pos).and_then(|chunk| chunk.heat),
+            };
+
+            if !chunk.is_empty() {
+                self.chunks.insert(pos, chunk);
+            }
+        }
+    }
+
+    fn step_chunk(
+        &self,
+        (x, y): ChunkPos,
+        word: impl Fn([u64; 8], u64) -> u64,
+    ) -> [u64; CHUNK as usize] {
+        let rows = |dx: isize, dy: isize| {
+            self.chunks
+                .get(&(x + dx, y + dy))
+                .map_or(&EMPTY_ROWS, |chunk| &chunk.rows)
+        };
+
+        // Chunk rows above, beside and below this one, west to east.
+        let grid = [-1, 0, 1].map(|dy| [-1, 0, 1].map(|dx| rows(dx, dy)));
+        let last = CHUNK as usize - 1;
+
+        // The row `r` of the 192-cell wide strip through this chunk, as the
+        // middle word plus its west and east shifted copies.
+        let strip = |r: isize| {
+            let (band, r) = match r {
+                -1 => (&grid[0], last),
+                64 => (&grid[2], 0),
+                r => (&grid[1], r as usize),
+            };
+            let [west, middle, east] = band.map(|rows| rows[r]);
+
+            (
+                (middle << 1) | (west >> 63),
+                middle,
+                (middle >> 1) | (east << 63),
+            )
+        };
+
+        let mut out = EMPTY_ROWS;
+
+        for (r, out_row) in out.iter_mut().enumerate() {
+            let r = r as isize;
+            let (above_w, above, above_e) = strip(r - 1);
+            let (center_w, center, center_e) = strip(r);
+            let (below_w, below, below_e) = strip(r + 1);
+
+            *out_row = word(
+                [
+                    above_w, above, above_e, center_w, center_e, below_w, below, below_e,
+                ],
+                center,
+            );
+        }
+
+        out
+    }
+
+    pub fn update_heat(&mut self, config: &Config) {
+        for chunk in self.chunks.values_mut() {
+            let heat = chunk.heat.get_or_insert_with(|| Box::new([0; CHUNK_CELLS]));
+
+            for (cy, &row) in chunk.rows.iter().enumerate() {
+                for cx in 0..CHUNK as usize {
+                    let i = cy * CHUNK as usize + cx;
+                    heat[i] = next_heat(row >> cx & 1 == 1, heat[i], config);
+                }
+            }
+        }
+
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        self.chunks.retain(|_, chunk| !chunk.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        board::{Board, Topology},
+        rules::{Rule, CONWAY, MAZE},
+    };
+
+    fn rulestring(rule: Rule) -> Rulestring {
+        match rule {
+            Rule::Rulestring(rule) => rule,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn matches_dense_step_away_from_the_edges() {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for rule in [CONWAY, MAZE].map(rulestring) {
+            // The soup can't reach the torus edges within 40 generations, so
+            // both boards must agree everywhere.
+            let mut dense = Board::new(200, 200);
+            let mut sparse = Sparse::default();
+            let (ox, oy) = (-1000, -70);
+
+            for y in 80..120 {
+                for x in 80..120 {
+                    let alive = rng.gen_bool(0.4);
+                    dense.set(x, y, alive);
+                    sparse.set(x + ox, y + oy, alive);
+                }
+            }
+
+            for _ in 0..40 {
+                dense.step_life(&rule, 1);
+                sparse.step(&rule, 3);
+            }
+
+            let mut expected: Vec<_> = (0..200)
+                .flat_map(|y| (0..200).map(move |x| (x, y)))
+                .filter(|&(x, y)| dense.is_alive(x, y))
+                .map(|(x, y)| (x + ox, y + oy))
+                .collect();
+            let mut actual = vec![];
+            sparse.for_each_tile_in(sparse.bounds().unwrap(), |x, y, _| actual.push((x, y)));
+            expected.sort();
+            actual.sort();
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn glider_travels_without_wrapping() {
+        let mut board = Board::with_topology(16, 16, Topology::Unbounded);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(x, y, true);
+        }
+
+        let rule = rulestring(CONWAY);
+        for _ in 0..4000 {
+            board.sparse.as_mut().unwrap().step(&rule, 1);
+        }
+
+        assert_eq!(board.live_bounds(), Some((1000, 1000, 1003, 1003)));
+        assert_eq!(board.chunk_count(), Some(1));
+    }
+}
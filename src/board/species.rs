@@ -0,0 +1,125 @@
+//! Multi-species stepping. The board steps as the underlying life-like rule,
+//! then survivors keep their species and newborns get theirs from the
+//! species their neighbours had before the step.
+//!
+//! Unbounded boards don't store states, so they don't step.
+
+use super::Board;
+use crate::rules::{species_of, Species, MAX_SPECIES, NEIGHBOURS};
+
+impl Board {
+    pub(super) fn step_species(&mut self, rule: &Species, threads: usize) {
+        let (width, height) = (self.width, self.height);
+        // Species plus one of every cell before the step, 0 for dead ones.
+        let before: Vec<u8> = (0..width * height)
+            .map(|i| species_of(self.get_u(i % width, i / width)).map_or(0, |s| s + 1))
+            .collect();
+
+        self.step_life(&rule.life, threads);
+        if !self.topology.is_torus() {
+            self.step_edges(&rule.life);
+        }
+
+        let mask = rule.life.neighbourhood.mask();
+        let mut states = vec![0; width * height];
+
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+
+                if !self.alive_at(x, y) {
+                    continue;
+                }
+
+                states[i] = if before[i] != 0 {
+                    before[i]
+                } else {
+                    let mut counts = [0; MAX_SPECIES];
+
+                    for (k, (dx, dy)) in NEIGHBOURS.iter().enumerate() {
+                        if mask >> k & 1 == 0 {
+                            continue;
+                        }
+
+                        if let Some((nx, ny)) = self.wrap_xy(x as isize + dx, y as isize + dy) {
+                            if let Some(species) = before[ny * width + nx].checked_sub(1) {
+                                counts[species as usize] += 1;
+                            }
+                        }
+                    }
+
+                    rule.newborn(&counts) + 1
+                };
+            }
+        }
+
+        self.states = Some(states);
+        self.activity.mark_all();
+    }
+
+    /// Live cells of each of the first `species` species over the board.
+    pub fn species_population(&self, species: u8) -> Vec<usize> {
+        let mut counts = vec![0; species as usize];
+        let everything = (0, 0, self.width as isize, self.height as isize);
+
+        self.for_each_tile_in(everything, |_, _, tile| {
+            if let Some(count) = species_of(tile).and_then(|s| counts.get_mut(s as usize)) {
+                *count += 1;
+            }
+        });
+
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Topology,
+        config::Config,
+        rules::{species_tile, IMMIGRATION, QUADLIFE},
+    };
+
+    #[test]
+    fn newborns_take_the_majority_species() {
+        let mut board = Board::new(10, 10);
+        // A blinker with two cells of the second species.
+        board.paint(3, 4, species_tile(0));
+        board.paint(4, 4, species_tile(1));
+        board.paint(5, 4, species_tile(1));
+
+        let config = Config {
+            rule: IMMIGRATION,
+            enable_heat: false,
+            ..Default::default()
+        };
+        board.update(&config);
+
+        assert_eq!(species_of(board.get(4, 3)), Some(1));
+        assert_eq!(species_of(board.get(4, 4)), Some(1));
+        assert_eq!(species_of(board.get(4, 5)), Some(1));
+        assert_eq!(board.species_population(2), vec![0, 3]);
+
+        let mut board = Board::new(10, 10);
+        for (x, species) in [(3, 0), (4, 1), (5, 2)] {
+            board.paint(x, 4, species_tile(species));
+        }
+        let config = Config {
+            rule: QUADLIFE,
+            ..config
+        };
+        board.update(&config);
+
+        assert_eq!(species_of(board.get(4, 3)), Some(3));
+        assert_eq!(board.species_population(4), vec![0, 1, 0, 2]);
+
+        // Unbounded boards would lose the species, so they don't step.
+        let mut board = Board::with_topology(10, 10, Topology::Unbounded);
+        for x in 3..6 {
+            board.set(x, 4, true);
+        }
+        board.update(&config);
+        assert_eq!(board.live_bounds(), Some((3, 4, 6, 5)));
+    }
+}
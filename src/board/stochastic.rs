@@ -0,0 +1,98 @@
+//! Stochastic rule stepping.
+//!
+//! Cells are visited one at a time in row order, so the random draws happen
+//! in the same order every run. Counts that are certain either way don't
+//! draw at all. Unbounded boards only visit cells next to live ones, so
+//! births without neighbours don't happen there.
+
+use rand::Rng;
+
+use super::Board;
+use crate::rules::Stochastic;
+
+impl Board {
+    pub(super) fn step_stochastic(&mut self, rule: &Stochastic) {
+        let (x0, y0, x1, y1) = if self.is_unbounded() {
+            let Some((x0, y0, x1, y1)) = self.live_bounds() else {
+                return;
+            };
+
+            (x0 - 1, y0 - 1, x1 + 1, y1 + 1)
+        } else {
+            (0, 0, self.width as isize, self.height as isize)
+        };
+
+        let mut flips = vec![];
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let alive = self.is_alive(x, y);
+                let n = self.count_neighbors(x, y, rule.neighbourhood);
+
+                if !alive && n == 0 && self.is_unbounded() {
+                    continue;
+                }
+
+                let chance = if alive {
+                    rule.survive[n]
+                } else {
+                    rule.spawn[n]
+                };
+                let next = chance >= 1. || (chance > 0. && self.rng.gen_bool(chance as f64));
+
+                if next != alive {
+                    flips.push((x, y, next));
+                }
+            }
+        }
+
+        for (x, y, alive) in flips {
+            self.set(x, y, alive);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Topology,
+        config::Config,
+        rules::{Rule, CONWAY},
+    };
+
+    #[test]
+    fn certain_rules_match_and_noisy_ones_repeat() {
+        let Rule::Rulestring(life) = CONWAY else {
+            unreachable!()
+        };
+        let run = |rule: Rule, topology| {
+            let config = Config {
+                rule,
+                enable_heat: false,
+                ..Default::default()
+            };
+            let mut board = Board::with_topology(32, 32, topology);
+            board.reseed(7);
+            board.randomize();
+
+            for _ in 0..10 {
+                board.update(&config);
+            }
+            board
+                .live_bounds()
+                .map(|_| serde_json::to_string(&board).unwrap())
+        };
+
+        for topology in [Topology::Torus, Topology::PlaneDead] {
+            let certain = Rule::Stochastic(Stochastic::from(life));
+            assert_eq!(run(certain, topology), run(CONWAY, topology), "{topology}");
+        }
+
+        let mut noisy = Stochastic::from(life);
+        noisy.survive[2] = 0.5;
+        let noisy = Rule::Stochastic(noisy);
+        assert_eq!(run(noisy, Topology::Torus), run(noisy, Topology::Torus));
+        assert_ne!(run(noisy, Topology::Torus), run(CONWAY, Topology::Torus));
+    }
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIs};
+
+/// How the edges of the board connect.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize, Display, EnumIs)]
+pub enum Topology {
+    /// Opposite edges are glued together.
+    #[default]
+    Torus,
+    /// Everything past the edges is dead.
+    #[strum(to_string = "Plane (dead outside)")]
+    PlaneDead,
+    /// Everything past the edges is alive.
+    #[strum(to_string = "Plane (live outside)")]
+    PlaneAlive,
+    /// Left and right are glued as on a torus, top and bottom with a twist.
+    #[strum(to_string = "Klein bottle")]
+    KleinBottle,
+    /// Both pairs of opposite edges are glued with a twist.
+    #[strum(to_string = "Cross-surface")]
+    CrossSurface,
+    /// The top edge is glued to the left edge and the bottom edge to the
+    /// right one, folding the board along its main diagonal.
+    Sphere,
+    /// No edges at all: the board grows as patterns spread.
+    Unbounded,
+}
+
+pub const TOPOLOGIES: &[Topology] = &[
+    Topology::Torus,
+    Topology::PlaneDead,
+    Topology::PlaneAlive,
+    Topology::KleinBottle,
+    Topology::CrossSurface,
+    Topology::Sphere,
+    Topology::Unbounded,
+];
+
+impl Topology {
+    /// Whether cells that don't map onto the board count as alive.
+    pub fn outside_alive(self) -> bool {
+        self == Topology::PlaneAlive
+    }
+
+    /// Maps any coordinate onto a cell of a `width` x `height` board, or
+    /// `None` if it lies outside a plane. On a sphere only cells past a single
+    /// edge are folded back; the diagonal neighbours of the corners are glue
+    /// points that hold no cell, so they also give `None`.
+    pub fn wrap(self, x: isize, y: isize, width: usize, height: usize) -> Option<(usize, usize)> {
+        let (w, h) = (width as isize, height as isize);
+        let inside = |x: isize, y: isize| {
+            ((0..w).contains(&x) && (0..h).contains(&y)).then_some((x as usize, y as usize))
+        };
+
+        match self {
+            Topology::Torus => Some((x.rem_euclid(w) as usize, y.rem_euclid(h) as usize)),
+            Topology::PlaneDead | Topology::PlaneAlive | Topology::Unbounded => inside(x, y),
+            Topology::KleinBottle => {
+                let x = if y.div_euclid(h) % 2 != 0 {
+                    w - 1 - x
+                } else {
+                    x
+                };
+
+                Some((x.rem_euclid(w) as usize, y.rem_euclid(h) as usize))
+            }
+            Topology::CrossSurface => {
+                let flip_x = y.div_euclid(h) % 2 != 0;
+                let flip_y = x.div_euclid(w) % 2 != 0;
+                let (x, y) = (x.rem_euclid(w), y.rem_euclid(h));
+
+                Some((
+                    if flip_x { w - 1 - x } else { x } as usize,
+                    if flip_y { h - 1 - y } else { y } as usize,
+                ))
+            }
+            Topology::Sphere => {
+                // Positions along one edge are rescaled onto the edge they are
+                // glued to, which only matters for boards that aren't square.
+                let along_x = |x: isize| x * h / w;
+                let along_y = |y: isize| y * w / h;
+
+                match (x, y) {
+                    (x, y) if (0..w).contains(&x) && y < 0 => inside(-1 - y, along_x(x)),
+                    (x, y) if (0..w).contains(&x) && y >= h => inside(w - 1 - (y - h), along_x(x)),
+                    (x, y) if (0..h).contains(&y) && x < 0 => inside(along_y(y), -1 - x),
+                    (x, y) if (0..h).contains(&y) && x >= w => inside(along_y(y), h - 1 - (x - w)),
+                    (x, y) => inside(x, y),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gluing_is_symmetric() {
+        for &topology in TOPOLOGIES.iter().filter(|t| !t.is_unbounded()) {
+            for (width, height) in [(5, 5), (8, 8), (7, 4)] {
+                // Sphere glues a horizontal edge to a vertical one, which only
+                // lines up cell for cell on square boards.
+                if topology == Topology::Sphere && width != height {
+                    continue;
+                }
+
+                let neighbours = |x: isize, y: isize| {
+                    (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                        .filter(|&d| d != (0, 0))
+                        .filter_map(|(dx, dy)| topology.wrap(x + dx, y + dy, width, height))
+                        .collect::<Vec<_>>()
+                };
+
+                for y in 0..height as isize {
+                    for x in 0..width as isize {
+                        for (nx, ny) in neighbours(x, y) {
+                            let back = neighbours(nx as isize, ny as isize);
+                            let count = |v: &Vec<_>, c| v.iter().filter(|&&p| p == c).count();
+
+                            assert_eq!(
+                                count(&neighbours(x, y), (nx, ny)),
+                                count(&back, (x as usize, y as usize)),
+                                "{topology} {width}x{height}: {x},{y} and {nx},{ny}",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
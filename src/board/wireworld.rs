@@ -0,0 +1,117 @@
+//! Wireworld stepping.
+//!
+//! The old alive plane, which holds the heads, is kept in the back buffer
+//! while cells are moved on, so conductors count the heads of the previous
+//! generation. Words without heads or states are skipped.
+
+use super::Board;
+use crate::rules::{wireworld::CONDUCTOR, wireworld::TAIL, NEIGHBOURS};
+
+impl Board {
+    pub(super) fn step_wireworld(&mut self) {
+        let (width, stride) = (self.width, self.stride);
+        let mut states = self
+            .states
+            .take()
+            .unwrap_or_else(|| vec![0; self.width * self.height]);
+
+        self.next.copy_from_slice(&self.alive);
+
+        for y in 0..self.height {
+            for word in 0..stride {
+                let i = y * stride + word;
+                let x0 = word * 64;
+                let cells = &mut states[y * width + x0..y * width + (x0 + 64).min(width)];
+
+                if self.next[i] == 0 && cells.iter().all(|&state| state == 0) {
+                    continue;
+                }
+
+                for (bit, state) in cells.iter_mut().enumerate() {
+                    let (x, mask) = (x0 + bit, 1 << bit);
+
+                    if self.next[i] & mask != 0 {
+                        self.alive[i] &= !mask;
+                        *state = TAIL;
+                    } else if *state == TAIL {
+                        *state = CONDUCTOR;
+                    } else if *state == CONDUCTOR && (1..=2).contains(&self.old_heads(x, y)) {
+                        self.alive[i] |= mask;
+                        *state = 0;
+                    } else {
+                        continue;
+                    }
+
+                    self.activity.mark(x, y);
+                }
+            }
+        }
+
+        self.states = Some(states);
+    }
+
+    /// Heads around an in-bounds cell in the previous generation.
+    fn old_heads(&self, x: usize, y: usize) -> usize {
+        NEIGHBOURS
+            .iter()
+            .filter(
+                |(dx, dy)| match self.wrap_xy(x as isize + dx, y as isize + dy) {
+                    Some((x, y)) => {
+                        let (i, mask) = self.bit(x, y);
+                        self.next[i] & mask != 0
+                    }
+                    None => self.topology.outside_alive(),
+                },
+            )
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{WireworldCell, WIREWORLD};
+
+    #[test]
+    fn electrons_run_along_wires() {
+        let config = crate::config::Config {
+            rule: WIREWORLD,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let mut board = Board::new(12, 5);
+
+        // A wire along row 2 that forks at x = 6, with an electron heading
+        // east.
+        for x in 1..11 {
+            board.set_tile(x, 2, WireworldCell::Conductor.tile());
+        }
+        for y in [1, 3] {
+            board.set_tile(7, y, WireworldCell::Conductor.tile());
+        }
+        board.set_tile(1, 2, WireworldCell::Tail.tile());
+        board.set_tile(2, 2, WireworldCell::Head.tile());
+
+        let cell = |board: &Board, x, y| WireworldCell::of(board.get(x, y));
+
+        board.update(&config);
+        assert_eq!(cell(&board, 1, 2), WireworldCell::Conductor);
+        assert_eq!(cell(&board, 2, 2), WireworldCell::Tail);
+        assert_eq!(cell(&board, 3, 2), WireworldCell::Head);
+
+        for _ in 0..3 {
+            board.update(&config);
+        }
+        // The head at (6, 2) lights up all three cells of the fork next.
+        assert_eq!(cell(&board, 6, 2), WireworldCell::Head);
+        board.update(&config);
+        for y in 1..4 {
+            assert_eq!(cell(&board, 7, y), WireworldCell::Head, "7,{y}");
+        }
+
+        // Three heads around (8, 2) are too many.
+        board.update(&config);
+        assert_eq!(cell(&board, 8, 2), WireworldCell::Conductor);
+        assert_eq!(board.live_bounds(), None);
+    }
+}
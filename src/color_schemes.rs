@@ -1,6 +1,8 @@
 use crate::{
     game::Game,
-    gcolor_u8, notify_info, tiny_str,
+    gcolor_u8, notify_info,
+    rules::MAX_SPECIES,
+    tiny_str,
     utils::{GColor, TinyStr},
 };
 use serde::{Deserialize, Serialize};
@@ -20,6 +22,11 @@ impl Game {
         config.dead_color = color_scheme.dead_color;
         config.alive_color = color_scheme.alive_color;
         config.hot_color = color_scheme.hot_color;
+        config.dying_color = color_scheme.dying_color;
+        config.wire_color = color_scheme.wire_color;
+        config.head_color = color_scheme.head_color;
+        config.tail_color = color_scheme.tail_color;
+        config.species_colors = color_scheme.species_colors;
         config.text_color = color_scheme.text_color;
         config.highlight_color = color_scheme.highlight_color;
         config.selection_color = color_scheme.line_color;
@@ -33,17 +40,60 @@ pub struct ColorScheme {
     pub dead_color: GColor,
     pub alive_color: GColor,
     pub hot_color: GColor,
+    /// First dying state of Generations rules, later ones fade to dead.
+    #[serde(default = "default_dying_color")]
+    pub dying_color: GColor,
+    /// Wireworld conductors, electron heads and electron tails.
+    #[serde(default = "default_wire_color")]
+    pub wire_color: GColor,
+    #[serde(default = "default_head_color")]
+    pub head_color: GColor,
+    #[serde(default = "default_tail_color")]
+    pub tail_color: GColor,
+    /// Species of Immigration and QuadLife rules, in order.
+    #[serde(default = "default_species_colors")]
+    pub species_colors: [GColor; MAX_SPECIES],
     pub text_color: GColor,
     pub highlight_color: GColor,
     pub line_color: GColor,
 }
 
+fn default_dying_color() -> GColor {
+    DARK.dying_color
+}
+
+fn default_wire_color() -> GColor {
+    DARK.wire_color
+}
+
+fn default_head_color() -> GColor {
+    DARK.head_color
+}
+
+fn default_tail_color() -> GColor {
+    DARK.tail_color
+}
+
+fn default_species_colors() -> [GColor; MAX_SPECIES] {
+    DARK.species_colors
+}
+
 pub const SOLARIZED: ColorScheme = ColorScheme {
     name: tiny_str!("Solarized"),
     bg_color: gcolor_u8!(0x00, 0x2B, 0x36, 255),
     dead_color: gcolor_u8!(0x07, 0x36, 0x42, 255),
     alive_color: gcolor_u8!(0xFD, 0xF6, 0xE3, 255),
     hot_color: gcolor_u8!(0x58, 0x6E, 0x75, 255),
+    dying_color: gcolor_u8!(0xCB, 0x4B, 0x16, 255),
+    wire_color: gcolor_u8!(0xB5, 0x89, 0x00, 255),
+    head_color: gcolor_u8!(0x26, 0x8B, 0xD2, 255),
+    tail_color: gcolor_u8!(0xDC, 0x32, 0x2F, 255),
+    species_colors: [
+        gcolor_u8!(0xFD, 0xF6, 0xE3, 255),
+        gcolor_u8!(0xDC, 0x32, 0x2F, 255),
+        gcolor_u8!(0x26, 0x8B, 0xD2, 255),
+        gcolor_u8!(0x85, 0x99, 0x00, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(0x26, 0x8B, 0xD2, 100),
@@ -55,6 +105,16 @@ pub const DARK: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(15, 15, 15, 255),
     alive_color: gcolor_u8!(240, 240, 240, 255),
     hot_color: gcolor_u8!(50, 50, 50, 255),
+    dying_color: gcolor_u8!(200, 120, 40, 255),
+    wire_color: gcolor_u8!(200, 150, 40, 255),
+    head_color: gcolor_u8!(70, 140, 255, 255),
+    tail_color: gcolor_u8!(230, 70, 50, 255),
+    species_colors: [
+        gcolor_u8!(240, 240, 240, 255),
+        gcolor_u8!(230, 70, 50, 255),
+        gcolor_u8!(70, 140, 255, 255),
+        gcolor_u8!(90, 200, 90, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(112, 158, 238, 100),
@@ -66,6 +126,16 @@ pub const LIGHT: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(240, 240, 240, 255),
     alive_color: gcolor_u8!(10, 10, 10, 255),
     hot_color: gcolor_u8!(180, 180, 180, 255),
+    dying_color: gcolor_u8!(200, 90, 30, 255),
+    wire_color: gcolor_u8!(190, 140, 20, 255),
+    head_color: gcolor_u8!(30, 90, 230, 255),
+    tail_color: gcolor_u8!(210, 50, 40, 255),
+    species_colors: [
+        gcolor_u8!(10, 10, 10, 255),
+        gcolor_u8!(210, 50, 40, 255),
+        gcolor_u8!(30, 90, 230, 255),
+        gcolor_u8!(40, 150, 60, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(10, 38, 117, 100),
@@ -77,6 +147,16 @@ pub const BLUE: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0, 0, 0, 255),
     alive_color: gcolor_u8!(255, 255, 255, 255),
     hot_color: gcolor_u8!(0, 0, 255, 255),
+    dying_color: gcolor_u8!(90, 160, 255, 255),
+    wire_color: gcolor_u8!(220, 170, 40, 255),
+    head_color: gcolor_u8!(120, 200, 255, 255),
+    tail_color: gcolor_u8!(0, 70, 200, 255),
+    species_colors: [
+        gcolor_u8!(255, 255, 255, 255),
+        gcolor_u8!(90, 160, 255, 255),
+        gcolor_u8!(0, 70, 200, 255),
+        gcolor_u8!(220, 170, 40, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(215, 139, 45, 100),
@@ -88,6 +168,16 @@ pub const RED: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0, 0, 0, 255),
     alive_color: gcolor_u8!(255, 255, 255, 255),
     hot_color: gcolor_u8!(255, 0, 0, 255),
+    dying_color: gcolor_u8!(255, 140, 0, 255),
+    wire_color: gcolor_u8!(230, 170, 40, 255),
+    head_color: gcolor_u8!(255, 255, 255, 255),
+    tail_color: gcolor_u8!(255, 0, 0, 255),
+    species_colors: [
+        gcolor_u8!(255, 255, 255, 255),
+        gcolor_u8!(255, 0, 0, 255),
+        gcolor_u8!(255, 140, 0, 255),
+        gcolor_u8!(90, 139, 223, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(90, 139, 223, 100),
@@ -99,6 +189,16 @@ pub const GREEN: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0, 0, 0, 255),
     alive_color: gcolor_u8!(255, 255, 255, 255),
     hot_color: gcolor_u8!(0, 255, 0, 255),
+    dying_color: gcolor_u8!(180, 255, 0, 255),
+    wire_color: gcolor_u8!(230, 170, 40, 255),
+    head_color: gcolor_u8!(255, 255, 255, 255),
+    tail_color: gcolor_u8!(0, 200, 0, 255),
+    species_colors: [
+        gcolor_u8!(255, 255, 255, 255),
+        gcolor_u8!(0, 200, 0, 255),
+        gcolor_u8!(180, 255, 0, 255),
+        gcolor_u8!(222, 94, 71, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(222, 94, 71, 100),
@@ -110,6 +210,16 @@ pub const YELLOW: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0, 0, 0, 255),
     alive_color: gcolor_u8!(255, 255, 255, 255),
     hot_color: gcolor_u8!(255, 255, 0, 255),
+    dying_color: gcolor_u8!(255, 160, 0, 255),
+    wire_color: gcolor_u8!(160, 110, 0, 255),
+    head_color: gcolor_u8!(255, 255, 255, 255),
+    tail_color: gcolor_u8!(255, 255, 0, 255),
+    species_colors: [
+        gcolor_u8!(255, 255, 255, 255),
+        gcolor_u8!(255, 255, 0, 255),
+        gcolor_u8!(255, 160, 0, 255),
+        gcolor_u8!(189, 90, 217, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(189, 90, 217, 100),
@@ -121,6 +231,16 @@ pub const PURPLE: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0, 0, 0, 255),
     alive_color: gcolor_u8!(255, 255, 255, 255),
     hot_color: gcolor_u8!(255, 0, 255, 255),
+    dying_color: gcolor_u8!(130, 80, 255, 255),
+    wire_color: gcolor_u8!(230, 170, 40, 255),
+    head_color: gcolor_u8!(255, 255, 255, 255),
+    tail_color: gcolor_u8!(255, 0, 255, 255),
+    species_colors: [
+        gcolor_u8!(255, 255, 255, 255),
+        gcolor_u8!(255, 0, 255, 255),
+        gcolor_u8!(130, 80, 255, 255),
+        gcolor_u8!(230, 170, 40, 255),
+    ],
     text_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 255),
     highlight_color: gcolor_u8!(0xFF, 0xFF, 0xFF, 50),
     line_color: gcolor_u8!(189, 124, 32, 100),
@@ -132,6 +252,16 @@ pub const GRUVBOX_YELLOW: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0x28, 0x28, 0x28, 255),
     alive_color: gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
     hot_color: gcolor_u8!(0xFA, 0xBD, 0x2F, 255),
+    dying_color: gcolor_u8!(0xFE, 0x80, 0x19, 255),
+    wire_color: gcolor_u8!(0xD7, 0x99, 0x21, 255),
+    head_color: gcolor_u8!(0x83, 0xA5, 0x98, 255),
+    tail_color: gcolor_u8!(0xFB, 0x49, 0x34, 255),
+    species_colors: [
+        gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
+        gcolor_u8!(0xFB, 0x49, 0x34, 255),
+        gcolor_u8!(0x83, 0xA5, 0x98, 255),
+        gcolor_u8!(0xFA, 0xBD, 0x2F, 255),
+    ],
     text_color: gcolor_u8!(0xFB, 0xF1, 0xC7, 255),
     highlight_color: gcolor_u8!(0xFB, 0xF1, 0xC7, 255),
     line_color: gcolor_u8!(0x45, 0x85, 0x88, 100),
@@ -143,6 +273,16 @@ pub const GRUVBOX_BLUE: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0x28, 0x28, 0x28, 255),
     alive_color: gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
     hot_color: gcolor_u8!(0x45, 0x85, 0x88, 255),
+    dying_color: gcolor_u8!(0x83, 0xA5, 0x98, 255),
+    wire_color: gcolor_u8!(0xD7, 0x99, 0x21, 255),
+    head_color: gcolor_u8!(0x83, 0xA5, 0x98, 255),
+    tail_color: gcolor_u8!(0xFB, 0x49, 0x34, 255),
+    species_colors: [
+        gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
+        gcolor_u8!(0xFB, 0x49, 0x34, 255),
+        gcolor_u8!(0x45, 0x85, 0x88, 255),
+        gcolor_u8!(0x8E, 0xC0, 0x7C, 255),
+    ],
     text_color: gcolor_u8!(0xFB, 0xF1, 0xC7, 255),
     highlight_color: gcolor_u8!(0xFB, 0xF1, 0xC7, 255),
     line_color: gcolor_u8!(0xD7, 0x99, 0x21, 100),
@@ -154,6 +294,16 @@ pub const GRUVBOX_GREY: ColorScheme = ColorScheme {
     dead_color: gcolor_u8!(0x28, 0x28, 0x28, 255),
     alive_color: gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
     hot_color: gcolor_u8!(0x50, 0x49, 0x45, 255),
+    dying_color: gcolor_u8!(0xA8, 0x99, 0x84, 255),
+    wire_color: gcolor_u8!(0xA8, 0x99, 0x84, 255),
+    head_color: gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
+    tail_color: gcolor_u8!(0xD6, 0x5D, 0x0E, 255),
+    species_colors: [
+        gcolor_u8!(0xEB, 0xDB, 0xB2, 255),
+        gcolor_u8!(0xD6, 0x5D, 0x0E, 255),
+        gcolor_u8!(0xA8, 0x99, 0x84, 255),
+        gcolor_u8!(0x68, 0x9D, 0x6A, 255),
+    ],
     text_color: gcolor_u8!(0xFB, 0xF1, 0xC7, 255),
     highlight_color: gcolor_u8!(0xFB, 0xF1, 0xC7, 255),
     line_color: gcolor_u8!(0xD6, 0x5D, 0x0E, 100),
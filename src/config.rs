@@ -1,23 +1,38 @@
 use dirs::data_dir;
 use macroquad::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{create_dir_all, read_to_string, File};
 use std::io::Write;
+use std::thread;
+use strum::Display;
 
-use crate::rules::{Rule, CONWAY};
+use crate::rules::{
+    species_of, species_tile, Material, ParamValue, Rule, Seeding, WireworldCell, CONWAY,
+    MAX_SPECIES,
+};
 use crate::{
+    board::{Tile, Topology},
     color_schemes::{ColorScheme, DARK},
     utils::GColor,
 };
 
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub width: usize,
     pub height: usize,
+    /// Used when creating a new board. Loaded boards keep their own.
+    pub topology: Topology,
     pub bg_color: GColor,
     pub dead_color: GColor,
     pub alive_color: GColor,
     pub hot_color: GColor,
+    pub dying_color: GColor,
+    pub wire_color: GColor,
+    pub head_color: GColor,
+    pub tail_color: GColor,
+    pub species_colors: [GColor; MAX_SPECIES],
     pub text_color: GColor,
     pub selection_color: GColor,
     pub tile_size: f32,
@@ -27,15 +42,171 @@ pub struct Config {
     pub soft_heat: bool,
     pub soft_heat_amount: u8,
     pub enable_heat: bool,
+    /// Lets heat stop births and burn out live cells under life-like rules.
+    pub couple_heat: bool,
+    pub heat_coupling: HeatCoupling,
     pub color_scheme: ColorScheme,
     pub color_scheme_last_frame: ColorScheme,
     pub pan_speed: f32,
     pub simulation_speed: usize,
     pub rule: Rule,
     pub brush_radius: usize,
+    /// How often turmite agents move each generation.
+    pub agent_steps: usize,
+    /// What the brush paints under Wireworld.
+    pub wireworld_brush: WireworldCell,
+    /// What the brush paints in falling sand mode.
+    pub sand_brush: Material,
+    /// Which species the brush paints under multi-species rules.
+    pub species_brush: u8,
+    /// Where reaction-diffusion rules put B when seeding the board.
+    pub seeding: Seeding,
+    /// Worker threads used to step the board.
+    pub threads: usize,
+    pub engine: Engine,
+    pub hashlife_memory_mb: usize,
+    /// Seeds the board's random number generator, so runs can be repeated.
+    pub seed: u64,
+    /// Rules saved from the rule editor, listed after the built-in ones.
+    pub saved_rules: Vec<(String, Rule)>,
+    /// Parameter values picked for each automaton, by automaton name and
+    /// then parameter name.
+    pub rule_params: BTreeMap<String, BTreeMap<String, ParamValue>>,
+}
+
+/// How life-like rules are stepped. Other rules always step on the board.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Display)]
+pub enum Engine {
+    Bitwise,
+    HashLife,
+}
+
+/// How heat feeds back into a life-like rule. Live cells gain `gain` heat
+/// each generation and die once they reach `burnout`, dead ones lose `decay`
+/// and can't be born while at least `birth_inhibit` hot.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeatCoupling {
+    pub gain: u8,
+    pub decay: u8,
+    pub birth_inhibit: u8,
+    pub burnout: u8,
+}
+
+impl HeatCoupling {
+    /// The settings of the maze cycle automaton.
+    pub const MAZE_CYCLE: Self = Self {
+        gain: 5,
+        decay: 1,
+        birth_inhibit: 100,
+        burnout: 255,
+    };
+
+    /// Whether a cell that was `alive` at `heat` can't be alive after the
+    /// next generation: live cells would burn out and dead ones can't be born.
+    pub fn too_hot(&self, alive: bool, heat: u8) -> bool {
+        if alive {
+            heat.saturating_add(self.gain) >= self.burnout
+        } else {
+            heat >= self.birth_inhibit
+        }
+    }
+
+    /// Heat of a cell after a generation in which it was `alive`.
+    pub fn next_heat(&self, alive: bool, heat: u8) -> u8 {
+        if alive {
+            heat.saturating_add(self.gain)
+        } else {
+            heat.saturating_sub(self.decay)
+        }
+    }
+}
+
+impl Default for HeatCoupling {
+    fn default() -> Self {
+        Self::MAZE_CYCLE
+    }
 }
 
 impl Config {
+    /// Color of a tile under the current rule, `None` for plain dead cells.
+    pub fn tile_color(&self, tile: Tile) -> Option<GColor> {
+        if self.rule.is_falling_sand() {
+            return Material::of(tile).color(tile.heat);
+        }
+
+        if self.rule.is_wireworld() {
+            return match WireworldCell::of(tile) {
+                WireworldCell::Empty => None,
+                WireworldCell::Conductor => Some(self.wire_color),
+                WireworldCell::Head => Some(self.head_color),
+                WireworldCell::Tail => Some(self.tail_color),
+            };
+        }
+
+        if let (Rule::Turmite(rule), true, state @ 2..) = (self.rule, tile.alive, tile.state) {
+            // Turmite colors past the first go from the alive color towards
+            // the dying color.
+            let fade = (state - 1) as f32 / (rule.colors - 1) as f32;
+            return Some(self.alive_color.blend(&self.dying_color, fade.min(1.)));
+        }
+
+        if let (Rule::Species(_), Some(species)) = (self.rule, species_of(tile)) {
+            return Some(self.species_colors[species as usize % MAX_SPECIES]);
+        }
+
+        if tile.alive {
+            return Some(self.alive_color);
+        }
+
+        if let (Some(states), state @ 2..) = (self.rule.decay_states(), tile.state) {
+            // Dying cells fade from the dying color towards the dead color.
+            let fade = (state - 2) as f32 / (states - 1) as f32;
+            return Some(self.dying_color.blend(&self.dead_color, fade.min(1.)));
+        }
+
+        (self.enable_heat && tile.heat() != 0).then(|| {
+            self.hot_color.blend(
+                &self.dead_color,
+                1. - (tile.heat() as f32 / 255.) * self.heat_intensity,
+            )
+        })
+    }
+
+    /// Color of a cell value under continuous rules, running from the dead
+    /// color through the dying color to the alive color. `None` for cells
+    /// that are all but empty.
+    pub fn value_color(&self, value: f32) -> Option<GColor> {
+        if value < 1. / 255. {
+            return None;
+        }
+
+        Some(if value < 0.5 {
+            self.dead_color.blend(&self.dying_color, value * 2.)
+        } else {
+            self.dying_color
+                .blend(&self.alive_color, (value - 0.5) * 2.)
+        })
+    }
+
+    /// What the brush and line tools paint under the current rule.
+    pub fn brush_tile(&self) -> Tile {
+        match self.rule {
+            Rule::Wireworld => self.wireworld_brush.tile(),
+            Rule::FallingSand => self.sand_brush.tile(),
+            Rule::Species(rule) => species_tile(self.species_brush.min(rule.species - 1)),
+            _ => Tile::from(true),
+        }
+    }
+
+    /// Name of the current rule, looking at saved rules before the built-in
+    /// ones.
+    pub fn rule_name(&self) -> String {
+        match self.saved_rules.iter().find(|(_, rule)| *rule == self.rule) {
+            Some((name, _)) => name.clone(),
+            None => self.rule.name(),
+        }
+    }
+
     pub fn save(&self) {
         let text = serde_json::to_string(&self).unwrap();
         let path = Self::path();
@@ -69,6 +240,7 @@ impl Default for Config {
         Self {
             width: 192,
             height: 108,
+            topology: Topology::Torus,
 
             tile_size: 16.0,
             heat_intensity: 0.5,
@@ -77,11 +249,32 @@ impl Default for Config {
             simulation_speed: 1,
             pan_speed: 100.0,
             brush_radius: 1,
+            wireworld_brush: WireworldCell::Conductor,
+            sand_brush: Material::Sand,
+            species_brush: 0,
+            seeding: Seeding::Perlin,
+            agent_steps: 1,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            engine: Engine::Bitwise,
+            hashlife_memory_mb: 512,
+            seed: 0,
+            saved_rules: vec![],
+            rule_params: BTreeMap::new(),
 
             bg_color: GColor::from_hex(0x002B36),
             dead_color: GColor::from_hex(0x073642),
             alive_color: GColor::from_hex(0xFDF6E3),
             hot_color: GColor::from_hex(0x586E75),
+            dying_color: GColor::from_hex(0xCB4B16),
+            wire_color: GColor::from_hex(0xB58900),
+            head_color: GColor::from_hex(0x268BD2),
+            tail_color: GColor::from_hex(0xDC322F),
+            species_colors: [
+                GColor::from_hex(0xFDF6E3),
+                GColor::from_hex(0xDC322F),
+                GColor::from_hex(0x268BD2),
+                GColor::from_hex(0x859900),
+            ],
             text_color: GColor::from_hex(0xFFFFFF),
             highlight_color: GColor::from_rgba(255, 255, 255, 50),
             selection_color: GColor::from_rgba(255, 255, 255, 50),
@@ -91,6 +284,8 @@ impl Default for Config {
 
             soft_heat: false,
             enable_heat: true,
+            couple_heat: false,
+            heat_coupling: HeatCoupling::MAZE_CYCLE,
 
             rule: CONWAY,
         }
@@ -1,6 +1,6 @@
 use macroquad::{miniquad::window::screen_size, text::draw_multiline_text};
 
-use crate::game::Game;
+use crate::{game::Game, rules::Rule};
 
 impl Game {
     pub fn render_debug_info(&self) {
@@ -24,6 +24,25 @@ impl Game {
     fn debug_info(&self) -> String {
         let mut info = format!("FPS: {:.2}\nIteration: {}\n", self.fps(), self.iter_count);
 
+        if let Some(hashlife) = &self.hashlife {
+            info = format!("{}HashLife nodes: {}\n", info, hashlife.node_count());
+        }
+
+        if let Some((active, total)) = self.board.active_chunks() {
+            info = format!("{}Active chunks: {}/{}\n", info, active, total);
+        }
+
+        if let Some(chunks) = self.board.chunk_count() {
+            info = format!("{}Chunks: {}\n", info, chunks);
+        }
+
+        // Unbounded boards don't keep species.
+        if let (Rule::Species(rule), false) = (self.config.rule, self.board.is_unbounded()) {
+            let counts = self.board.species_population(rule.species);
+            let counts: Vec<String> = counts.iter().map(|n| n.to_string()).collect();
+            info = format!("{}Species: {}\n", info, counts.join(" / "));
+        }
+
         if self.paused {
             info = format!("{}\nPaused", info);
         }
@@ -1,6 +1,15 @@
 use crate::{
-    board::Board, config::Config, input::Tool, notifications::NotificationState, notify_info,
-    recording::Recording, rendering::Camera, ui::UiState, utils::Vec2I,
+    board::Board,
+    config::{Config, Engine},
+    hashlife::HashLife,
+    input::Tool,
+    notifications::NotificationState,
+    notify_info, notify_warning,
+    recording::Recording,
+    rendering::Camera,
+    rules::{Rule, Rulestring},
+    ui::UiState,
+    utils::Vec2I,
 };
 use fps_ticker::Fps;
 use log::info;
@@ -24,14 +33,19 @@ pub struct Game {
     pub selected_tool: Tool,
     pub notifications: NotificationState,
     pub recording: Option<Arc<Mutex<Recording>>>,
+    /// Kept between steps so its result cache carries over.
+    pub hashlife: Option<HashLife>,
 }
 
 impl Game {
     pub fn new() -> Self {
         let config = Config::load();
 
+        let mut board = Board::with_topology(config.width, config.height, config.topology);
+        board.reseed(config.seed);
+
         Self {
-            board: Board::new(config.width, config.height),
+            board,
             config,
             iter_count: 0,
             camera: Camera::default(),
@@ -44,6 +58,7 @@ impl Game {
             selected_tool: Tool::Brush,
             notifications: NotificationState::new(),
             recording: None,
+            hashlife: None,
         }
     }
 
@@ -58,10 +73,10 @@ impl Game {
             }
 
             if !self.paused {
-                for _ in 0..self.config.simulation_speed {
-                    self.iter_count += 1;
-                    self.board.update(&self.config);
-                }
+                self.advance(
+                    self.config.simulation_speed as u64,
+                    self.config.engine == Engine::HashLife,
+                );
             }
 
             self.handle_input();
@@ -84,6 +99,71 @@ impl Game {
         }
     }
 
+    /// Runs `n` generations. Life-like rules go through HashLife when
+    /// `use_hashlife` is set and [`hashlife_blocker`] allows it; everything
+    /// else is stepped on the board one generation at a time.
+    pub fn advance(&mut self, n: u64, use_hashlife: bool) {
+        advance_board(
+            &mut self.board,
+            &mut self.hashlife,
+            &self.config,
+            n,
+            use_hashlife,
+        );
+        self.iter_count += n;
+    }
+
+    /// Jumps ahead by `n` generations, using HashLife whenever possible.
+    /// Jumps that have to be stepped one generation at a time are refused
+    /// past [`MAX_STEPPED_JUMP`], as they would hang the app.
+    pub fn jump(&mut self, n: u64) {
+        if let Some(reason) = hashlife_blocker(&self.board, &self.config) {
+            if n > MAX_STEPPED_JUMP {
+                notify_warning!(
+                    self,
+                    "HashLife can't be used because {}, and {} generations is too many to step one by one (at most {}).",
+                    reason,
+                    n,
+                    MAX_STEPPED_JUMP
+                );
+                return;
+            }
+
+            notify_warning!(
+                self,
+                "HashLife can't be used because {}, stepping {} generations one by one.",
+                reason,
+                n
+            );
+        }
+
+        self.advance(n, true);
+        notify_info!(self, "Jumped {} generations.", n);
+    }
+
+    /// Warns when the current rule can't run as it should on an unbounded
+    /// board, which doesn't store cell states.
+    pub fn warn_if_rule_cant_step(&mut self) {
+        if !self.board.is_unbounded() {
+            return;
+        }
+
+        if self.config.rule.needs_bounds() {
+            notify_warning!(
+                self,
+                "{} needs a bounded topology, so the board won't change.",
+                self.config.rule_name()
+            );
+        } else if let Rule::Turmite(rule) = self.config.rule {
+            if rule.colors > 2 {
+                notify_warning!(
+                    self,
+                    "Unbounded boards only keep two colors, so the turmite's other colors are lost."
+                );
+            }
+        }
+    }
+
     pub fn reload_saves(&mut self) {
         self.saves = Self::get_saves();
     }
@@ -124,10 +204,132 @@ impl Game {
         let text = fs::read_to_string(&path).map_err(|e| e.to_string())?;
         let board: Board = serde_json::from_str(&text).map_err(|e| e.to_string())?;
 
+        self.config.topology = board.topology();
         self.board = board;
+        self.board.reseed(self.config.seed);
 
         notify_info!(self, "Loaded board from {}", path);
 
         Ok(())
     }
 }
+
+/// Most generations a jump steps on the board one at a time.
+pub const MAX_STEPPED_JUMP: u64 = 10_000;
+
+/// Why HashLife can't step `board` under the current rule, or `None` if it
+/// can. HashLife works on an infinite plane, so it only stands in for
+/// unbounded boards: even on planes with dead outside, patterns would run
+/// past the edges and come back.
+pub fn hashlife_blocker(board: &Board, config: &Config) -> Option<&'static str> {
+    let Rule::Rulestring(rule) = config.rule else {
+        return Some("the rule isn't life-like");
+    };
+
+    if !HashLife::supports(&rule) {
+        Some("it does not support B0 rules")
+    } else if config.couple_heat {
+        Some("heat is coupled to the rule")
+    } else if !board.is_unbounded() {
+        Some("the board has edges")
+    } else {
+        None
+    }
+}
+
+/// Steps `board` by `n` generations, through `hashlife` when `use_hashlife`
+/// is set and nothing blocks it.
+fn advance_board(
+    board: &mut Board,
+    hashlife: &mut Option<HashLife>,
+    config: &Config,
+    n: u64,
+    use_hashlife: bool,
+) {
+    if let (true, None, Rule::Rulestring(rule)) =
+        (use_hashlife, hashlife_blocker(board, config), config.rule)
+    {
+        let hashlife = ensure_hashlife(hashlife, rule, config);
+        hashlife.load_board(board);
+        hashlife.step(n);
+        hashlife.write_board(board);
+
+        if config.enable_heat {
+            board.update_heat(config);
+        }
+
+        board.add_generations(n);
+        return;
+    }
+
+    for _ in 0..n {
+        board.update(config);
+    }
+}
+
+/// Returns the cached HashLife universe, replacing it if the rule changed.
+fn ensure_hashlife<'a>(
+    hashlife: &'a mut Option<HashLife>,
+    rule: Rulestring,
+    config: &Config,
+) -> &'a mut HashLife {
+    if hashlife.as_ref().map(HashLife::rule) != Some(rule) {
+        *hashlife = None;
+    }
+
+    let hashlife = hashlife.get_or_insert_with(|| {
+        HashLife::new(rule, config.hashlife_memory_mb)
+            .expect("caller checked that the rule is supported")
+    });
+    hashlife.set_memory_limit(config.hashlife_memory_mb);
+
+    hashlife
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Topology, TOPOLOGIES},
+        rules::CONWAY,
+    };
+
+    #[test]
+    fn engines_agree_on_every_topology() {
+        let config = Config {
+            rule: CONWAY,
+            enable_heat: false,
+            ..Default::default()
+        };
+
+        for &topology in TOPOLOGIES {
+            // A glider heading south east from near the corner, which has
+            // crossed both edges after 40 generations.
+            let mut glider = Board::with_topology(16, 16, topology);
+            for (x, y) in [(12, 10), (13, 11), (11, 12), (12, 12), (13, 12)] {
+                glider.set(x, y, true);
+            }
+
+            let mut bitwise = glider.clone();
+            advance_board(&mut bitwise, &mut None, &config, 40, false);
+            let mut hashlife = None;
+            let mut stepped = glider;
+            advance_board(&mut stepped, &mut hashlife, &config, 40, true);
+
+            assert_eq!(
+                hashlife.is_some(),
+                topology == Topology::Unbounded,
+                "{topology}"
+            );
+            for y in -8..32 {
+                for x in -8..32 {
+                    assert_eq!(
+                        bitwise.get(x, y).alive,
+                        stepped.get(x, y).alive,
+                        "{topology} cell {x},{y}"
+                    );
+                }
+            }
+        }
+    }
+}
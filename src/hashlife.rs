@@ -0,0 +1,633 @@
+//! Memoized quadtree (HashLife) engine for life-like rules.
+//!
+//! The universe is an unbounded plane stored as a hash-consed quadtree, so
+//! identical regions share a node and the future of every node is computed
+//! only once. Boards are copied into the tree before stepping and copied back
+//! afterwards; on bounded boards, live cells that wandered off the board are
+//! dropped on the way back, so patterns that reach the edge behave as if the
+//! board had no wrap.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    board::Board,
+    rules::{Rulestring, NEIGHBOURS},
+};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Longest jump offered in the UI. Gliders travel a quarter of that, which
+/// still lands on the board's coordinates.
+pub const MAX_JUMP: u64 = u64::MAX >> 2;
+
+/// Rough cost of one node across the arena, the index and the result cache.
+const BYTES_PER_NODE: usize = 96;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Quad {
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    quad: Quad,
+    level: u8,
+    population: u64,
+}
+
+pub struct HashLife {
+    rule: Rulestring,
+    nodes: Vec<Node>,
+    index: HashMap<Quad, NodeId>,
+    /// Centre of a node after `2^j` generations, keyed by node and `j`.
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// The empty node of every level built so far.
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// World coordinates of the root's top-left cell. Wider than board
+    /// coordinates, as long jumps grow the root far past them.
+    origin: (i128, i128),
+    max_nodes: usize,
+}
+
+impl HashLife {
+    /// Rules with B0 turn the infinite empty background on, which a sparse
+    /// quadtree cannot represent.
+    pub fn supports(rule: &Rulestring) -> bool {
+        !rule.spawn[0]
+    }
+
+    pub fn new(rule: Rulestring, memory_mb: usize) -> Result<Self> {
+        if !Self::supports(&rule) {
+            bail!("HashLife does not support rules with B0");
+        }
+
+        let leaf = |population| Node {
+            quad: Quad {
+                nw: DEAD,
+                ne: DEAD,
+                sw: DEAD,
+                se: DEAD,
+            },
+            level: 0,
+            population,
+        };
+
+        let mut hashlife = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: (0, 0),
+            max_nodes: 0,
+        };
+        hashlife.set_memory_limit(memory_mb);
+        hashlife.root = hashlife.empty(3);
+
+        Ok(hashlife)
+    }
+
+    pub fn rule(&self) -> Rulestring {
+        self.rule
+    }
+
+    /// The cap is soft: it is checked between steps, so a single huge step
+    /// may overshoot it before the cache is collected.
+    pub fn set_memory_limit(&mut self, memory_mb: usize) {
+        self.max_nodes = (memory_mb * 1024 * 1024 / BYTES_PER_NODE).max(1024);
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Replaces the universe with the live cells of `board`. The result cache
+    /// is kept, so reloading a similar board is cheap.
+    pub fn load_board(&mut self, board: &Board) {
+        let rect = if board.is_unbounded() {
+            board.live_bounds().unwrap_or_default()
+        } else {
+            (0, 0, board.width() as isize, board.height() as isize)
+        };
+        let (x0, y0, x1, y1) = rect;
+
+        let size = ((x1 - x0).max(y1 - y0) as usize).max(8);
+        let level = size.next_power_of_two().trailing_zeros() as u8;
+
+        self.root = self.build(board, rect, level, x0, y0);
+        self.origin = (x0 as i128, y0 as i128);
+    }
+
+    /// Writes the part of the universe that overlaps `board` back into it.
+    pub fn write_board(&self, board: &mut Board) {
+        board.clear_alive();
+
+        let clip = if board.is_unbounded() {
+            (
+                isize::MIN as i128,
+                isize::MIN as i128,
+                isize::MAX as i128,
+                isize::MAX as i128,
+            )
+        } else {
+            (0, 0, board.width() as i128, board.height() as i128)
+        };
+        let (x, y) = self.origin;
+        self.paint(board, clip, self.root, x, y);
+    }
+
+    /// Advances the universe by `n` generations.
+    pub fn step(&mut self, n: u64) {
+        for j in 0..u64::BITS as u8 {
+            if n >> j & 1 == 1 {
+                self.step_pow2(j);
+            }
+        }
+    }
+
+    /// Advances the universe by `2^j` generations.
+    pub fn step_pow2(&mut self, j: u8) {
+        // The pattern has to sit in the centre quarter of a root that is tall
+        // enough for the step, plus one more ring of empty space so nothing
+        // grows out of the result.
+        while self.level() < j + 2 || !self.is_centered() {
+            self.expand();
+        }
+        self.expand();
+
+        let shift = 1 << (self.level() - 2);
+        self.root = self.successor(self.root, j);
+        self.origin.0 += shift;
+        self.origin.1 += shift;
+
+        if self.nodes.len() > self.max_nodes {
+            self.collect_garbage();
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    fn level(&self) -> u8 {
+        self.node(self.root).level
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let quad = Quad { nw, ne, sw, se };
+
+        if let Some(&id) = self.index.get(&quad) {
+            return id;
+        }
+
+        let population = [nw, ne, sw, se]
+            .iter()
+            .map(|&child| self.node(child).population)
+            .sum();
+        let id = self.nodes.len() as NodeId;
+
+        self.nodes.push(Node {
+            quad,
+            level: self.node(nw).level + 1,
+            population,
+        });
+        self.index.insert(quad, id);
+
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let next = self.join(e, e, e, e);
+            self.empty.push(next);
+        }
+
+        self.empty[level as usize]
+    }
+
+    /// Builds the level-`level` node whose top-left cell is `(x, y)`, reading
+    /// only cells inside `rect`.
+    fn build(
+        &mut self,
+        board: &Board,
+        rect: (isize, isize, isize, isize),
+        level: u8,
+        x: isize,
+        y: isize,
+    ) -> NodeId {
+        let (_, _, x1, y1) = rect;
+
+        if x >= x1 || y >= y1 {
+            return self.empty(level);
+        }
+
+        if level == 0 {
+            return if board.is_alive(x, y) { ALIVE } else { DEAD };
+        }
+
+        let half = 1 << (level - 1);
+        let nw = self.build(board, rect, level - 1, x, y);
+        let ne = self.build(board, rect, level - 1, x + half, y);
+        let sw = self.build(board, rect, level - 1, x, y + half);
+        let se = self.build(board, rect, level - 1, x + half, y + half);
+
+        self.join(nw, ne, sw, se)
+    }
+
+    fn paint(
+        &self,
+        board: &mut Board,
+        clip: (i128, i128, i128, i128),
+        id: NodeId,
+        x: i128,
+        y: i128,
+    ) {
+        let node = self.node(id);
+        let size = 1i128 << node.level;
+        let (x0, y0, x1, y1) = clip;
+
+        if node.population == 0 || x >= x1 || y >= y1 || x + size <= x0 || y + size <= y0 {
+            return;
+        }
+
+        if node.level == 0 {
+            board.set(x as isize, y as isize, true);
+            return;
+        }
+
+        let half = size / 2;
+        let Quad { nw, ne, sw, se } = node.quad;
+
+        self.paint(board, clip, nw, x, y);
+        self.paint(board, clip, ne, x + half, y);
+        self.paint(board, clip, sw, x, y + half);
+        self.paint(board, clip, se, x + half, y + half);
+    }
+
+    /// Whether all live cells sit in the central quarter of the root.
+    fn is_centered(&self) -> bool {
+        let Quad { nw, ne, sw, se } = self.node(self.root).quad;
+        let outer = [
+            (nw, [true, true, true, false]),
+            (ne, [true, true, false, true]),
+            (sw, [true, false, true, true]),
+            (se, [false, true, true, true]),
+        ];
+
+        outer.iter().all(|&(child, outer_quads)| {
+            let Quad { nw, ne, sw, se } = self.node(child).quad;
+
+            [nw, ne, sw, se]
+                .iter()
+                .zip(outer_quads)
+                .all(|(&grandchild, outer)| !outer || self.node(grandchild).population == 0)
+        })
+    }
+
+    /// Doubles the root, keeping the current root in the centre.
+    fn expand(&mut self) {
+        let level = self.level();
+        let e = self.empty(level - 1);
+        let Quad { nw, ne, sw, se } = self.node(self.root).quad;
+
+        let nw = self.join(e, e, e, nw);
+        let ne = self.join(e, e, ne, e);
+        let sw = self.join(e, sw, e, e);
+        let se = self.join(se, e, e, e);
+
+        self.root = self.join(nw, ne, sw, se);
+
+        let shift = 1 << (level - 1);
+        self.origin.0 -= shift;
+        self.origin.1 -= shift;
+    }
+
+    /// The centre half of a node, without stepping.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let Quad { nw, ne, sw, se } = self.node(id).quad;
+
+        self.join(
+            self.node(nw).quad.se,
+            self.node(ne).quad.sw,
+            self.node(sw).quad.ne,
+            self.node(se).quad.nw,
+        )
+    }
+
+    /// The centre half of a level-`k` node after `2^j` generations, where
+    /// `j <= k - 2`.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.node(id);
+
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let result = if node.level == 2 {
+            self.step_leaf(id)
+        } else {
+            let full_speed = j == node.level - 2;
+            let parts = self.nine_parts(id);
+
+            let mut c = [DEAD; 9];
+            for (c, &part) in c.iter_mut().zip(&parts) {
+                *c = if full_speed {
+                    self.successor(part, j - 1)
+                } else {
+                    self.center(part)
+                };
+            }
+
+            let j = if full_speed { j - 1 } else { j };
+
+            let nw = self.join(c[0], c[1], c[3], c[4]);
+            let ne = self.join(c[1], c[2], c[4], c[5]);
+            let sw = self.join(c[3], c[4], c[6], c[7]);
+            let se = self.join(c[4], c[5], c[7], c[8]);
+
+            let nw = self.successor(nw, j);
+            let ne = self.successor(ne, j);
+            let sw = self.successor(sw, j);
+            let se = self.successor(se, j);
+
+            self.join(nw, ne, sw, se)
+        };
+
+        self.results.insert((id, j), result);
+
+        result
+    }
+
+    /// The nine overlapping half-size nodes of a node, in reading order.
+    fn nine_parts(&mut self, id: NodeId) -> [NodeId; 9] {
+        let Quad { nw, ne, sw, se } = self.node(id).quad;
+        let [nw_q, ne_q, sw_q, se_q] = [nw, ne, sw, se].map(|child| self.node(child).quad);
+
+        let n = self.join(nw_q.ne, ne_q.nw, nw_q.se, ne_q.sw);
+        let w = self.join(nw_q.sw, nw_q.se, sw_q.nw, sw_q.ne);
+        let c = self.join(nw_q.se, ne_q.sw, sw_q.ne, se_q.nw);
+        let e = self.join(ne_q.sw, ne_q.se, se_q.nw, se_q.ne);
+        let s = self.join(sw_q.ne, se_q.nw, sw_q.se, se_q.sw);
+
+        [nw, n, ne, w, c, e, sw, s, se]
+    }
+
+    /// Steps the centre 2x2 of a 4x4 node by one generation.
+    fn step_leaf(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.cell(id, x, y);
+            }
+        }
+
+        let next = |x: usize, y: usize| {
+            let n = NEIGHBOURS
+                .iter()
+                .enumerate()
+                .filter(|(k, (dx, dy))| {
+                    self.rule.neighbourhood.mask() >> k & 1 == 1
+                        && cells[y.wrapping_add_signed(*dy)][x.wrapping_add_signed(*dx)]
+                })
+                .count();
+
+            let alive = if cells[y][x] {
+                self.rule.survive[n]
+            } else {
+                self.rule.spawn[n]
+            };
+
+            if alive {
+                ALIVE
+            } else {
+                DEAD
+            }
+        };
+
+        let quad = [next(1, 1), next(2, 1), next(1, 2), next(2, 2)];
+
+        self.join(quad[0], quad[1], quad[2], quad[3])
+    }
+
+    fn cell(&self, id: NodeId, x: usize, y: usize) -> bool {
+        let node = self.node(id);
+
+        if node.level == 0 {
+            return id == ALIVE;
+        }
+
+        let half = 1 << (node.level - 1);
+        let Quad { nw, ne, sw, se } = node.quad;
+
+        match (x < half, y < half) {
+            (true, true) => self.cell(nw, x, y),
+            (false, true) => self.cell(ne, x - half, y),
+            (true, false) => self.cell(sw, x, y - half),
+            (false, false) => self.cell(se, x - half, y - half),
+        }
+    }
+
+    /// Drops every node that is not part of the current root, along with the
+    /// whole result cache.
+    fn collect_garbage(&mut self) {
+        let leaves = self.nodes[..2].to_vec();
+        let old = std::mem::replace(&mut self.nodes, leaves);
+        let mut remap = HashMap::new();
+
+        self.index.clear();
+        self.results.clear();
+        self.empty.truncate(1);
+        self.root = self.copy_node(&old, self.root, &mut remap);
+    }
+
+    fn copy_node(
+        &mut self,
+        old: &[Node],
+        id: NodeId,
+        remap: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if id == DEAD || id == ALIVE {
+            return id;
+        }
+
+        if let Some(&new) = remap.get(&id) {
+            return new;
+        }
+
+        let Quad { nw, ne, sw, se } = old[id as usize].quad;
+        let nw = self.copy_node(old, nw, remap);
+        let ne = self.copy_node(old, ne, remap);
+        let sw = self.copy_node(old, sw, remap);
+        let se = self.copy_node(old, se, remap);
+        let new = self.join(nw, ne, sw, se);
+
+        remap.insert(id, new);
+
+        new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        board::Topology,
+        config::Config,
+        rules::{Neighbourhood, Rule, CONWAY, MAZE},
+    };
+
+    fn soup(rng: &mut StdRng) -> Board {
+        let mut board = Board::new(160, 144);
+
+        for y in 60..90 {
+            for x in 65..95 {
+                board.set(x, y, rng.gen_bool(0.45));
+            }
+        }
+
+        board
+    }
+
+    fn step_bitwise(board: &mut Board, rule: Rulestring, generations: u64) {
+        let config = Config {
+            rule: Rule::Rulestring(rule),
+            enable_heat: false,
+            ..Config::default()
+        };
+
+        for _ in 0..generations {
+            board.update(&config);
+        }
+    }
+
+    fn assert_same(a: &Board, b: &Board) {
+        for y in 0..a.height() {
+            for x in 0..a.width() {
+                assert_eq!(a.get_u(x, y).alive, b.get_u(x, y).alive, "cell {x},{y}");
+            }
+        }
+    }
+
+    fn rules() -> Vec<Rulestring> {
+        let mut rules = vec![];
+        for rule in [CONWAY, MAZE] {
+            if let Rule::Rulestring(rule) = rule {
+                rules.push(rule);
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for neighbourhood in [
+            Neighbourhood::Moore,
+            Neighbourhood::Hexagonal,
+            Neighbourhood::VonNeumann,
+        ]
+        .repeat(2)
+        {
+            let mut spawn: [bool; 9] = std::array::from_fn(|_| rng.gen_bool(0.5));
+            spawn[0] = false;
+            rules.push(Rulestring {
+                survive: std::array::from_fn(|_| rng.gen_bool(0.5)),
+                spawn,
+                neighbourhood,
+            });
+        }
+
+        rules
+    }
+
+    #[test]
+    fn matches_bitwise_steps() {
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for rule in rules() {
+            for generations in [1, 2, 5, 16, 37] {
+                let mut expected = soup(&mut rng);
+                let mut hashlife = HashLife::new(rule, 64).unwrap();
+
+                hashlife.load_board(&expected);
+                hashlife.step(generations);
+                step_bitwise(&mut expected, rule, generations);
+
+                let mut actual = Board::new(expected.width(), expected.height());
+                hashlife.write_board(&mut actual);
+
+                assert_same(&actual, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn collecting_garbage_keeps_the_pattern() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let Rule::Rulestring(rule) = CONWAY else {
+            unreachable!()
+        };
+
+        let mut expected = soup(&mut rng);
+        let mut hashlife = HashLife::new(rule, 0).unwrap();
+
+        hashlife.load_board(&expected);
+        for _ in 0..4 {
+            hashlife.step_pow2(3);
+        }
+        step_bitwise(&mut expected, rule, 32);
+
+        let mut actual = Board::new(expected.width(), expected.height());
+        hashlife.write_board(&mut actual);
+
+        assert_same(&actual, &expected);
+    }
+
+    #[test]
+    fn jumps_as_far_as_the_ui_allows() {
+        let Rule::Rulestring(rule) = CONWAY else {
+            unreachable!()
+        };
+
+        let mut board = Board::with_topology(16, 16, Topology::Unbounded);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(x, y, true);
+        }
+
+        let mut hashlife = HashLife::new(rule, 64).unwrap();
+        hashlife.load_board(&board);
+        hashlife.step(MAX_JUMP);
+        hashlife.write_board(&mut board);
+
+        // The glider moves a cell diagonally every four generations.
+        let moved = (MAX_JUMP / 4) as isize;
+        let (x0, y0, x1, y1) = board.live_bounds().unwrap();
+        assert_eq!((x1 - x0, y1 - y0), (3, 3));
+        assert!((x0 - moved).abs() <= 1 && (y0 - moved).abs() <= 1);
+    }
+
+    #[test]
+    fn rejects_b0() {
+        let rule = Rulestring {
+            survive: [false; 9],
+            spawn: [true; 9],
+            neighbourhood: Neighbourhood::Moore,
+        };
+
+        assert!(HashLife::new(rule, 64).is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{game::Game, utils::Vec2I};
+use crate::{
+    board::{Agent, Tile},
+    game::Game,
+    rules::Turn,
+    utils::Vec2I,
+};
 use macroquad::prelude::*;
 use strum::{Display, EnumIs};
 
@@ -13,6 +18,9 @@ pub enum Tool {
         start: Option<Vec2I>,
         end: Option<Vec2I>,
     },
+    /// Places turmite agents, turning those clicked again, and removes them
+    /// with the right button.
+    Agent,
 }
 
 impl Game {
@@ -39,7 +47,7 @@ impl Game {
                 } else if start.is_some() && end.is_some() {
                     if let Some(line) = self.get_brush_line() {
                         for (x, y) in line {
-                            self.board.set(x, y, true);
+                            self.board.paint(x, y, self.config.brush_tile());
                         }
                     }
 
@@ -62,7 +70,7 @@ impl Game {
                 {
                     if let Some(line) = self.get_brush_line() {
                         for (x, y) in line {
-                            self.board.set(x, y, true);
+                            self.board.paint(x, y, self.config.brush_tile());
                         }
                     }
 
@@ -72,6 +80,27 @@ impl Game {
                     }
                 }
             }
+            Tool::Agent => {
+                let Vec2I { x, y } = mouse_pos;
+
+                if is_mouse_button_pressed(MouseButton::Left) && self.board.is_inside(x, y) {
+                    let agents = self.board.agents_mut();
+
+                    match agents.iter_mut().find(|a| (a.x, a.y) == (x, y)) {
+                        Some(agent) => agent.heading = agent.heading.turn(Turn::Right),
+                        None => agents.push(Agent::new(x, y)),
+                    }
+                } else if is_mouse_button_pressed(MouseButton::Right) {
+                    self.board.agents_mut().retain(|a| (a.x, a.y) != (x, y));
+                }
+            }
+        }
+
+        // Keys typed into the settings belong to them, not to the board.
+        let mut typing = false;
+        egui_macroquad::cfg(|ctx| typing = ctx.wants_keyboard_input());
+        if typing {
+            return;
         }
 
         if is_key_pressed(KeyCode::Space) {
@@ -82,6 +111,10 @@ impl Game {
             self.board.clear();
         }
 
+        if is_key_pressed(KeyCode::K) {
+            self.crop_to_selection();
+        }
+
         if is_key_pressed(KeyCode::N) {
             self.board.update(&self.config);
         }
@@ -100,11 +133,17 @@ impl Game {
 
         let delta = mouse_delta_position();
 
-        self.camera.offset -= (delta / self.camera.zoom) * self.config.pan_speed;
+        self.camera.offset -= ((delta / self.camera.zoom) * self.config.pan_speed).as_dvec2();
     }
 
+    /// Paints with the brush's tile, or erases for `to == false`.
     fn drawing(&mut self, to: bool) {
         let pos = self.mouse_pos();
+        let to = if to {
+            self.config.brush_tile()
+        } else {
+            Tile::from(false)
+        };
 
         if let Some(last_pos) = &self.mouse_pos_last_frame {
             if self.config.brush_radius == 1 {
@@ -112,20 +151,19 @@ impl Game {
                     .set_line(pos.x, pos.y, last_pos.x, last_pos.y, to);
             } else {
                 self.board.draw_line(
-                    pos.x as usize,
-                    pos.y as usize,
-                    last_pos.x as usize,
-                    last_pos.y as usize,
+                    pos.x,
+                    pos.y,
+                    last_pos.x,
+                    last_pos.y,
                     self.config.brush_radius,
                     to,
                 );
             }
         } else {
             if self.config.brush_radius == 1 {
-                self.board.set(pos.x, pos.y, to);
+                self.board.paint(pos.x, pos.y, to);
             } else {
-                self.board
-                    .draw(pos.x as usize, pos.y as usize, self.config.brush_radius, to);
+                self.board.draw(pos.x, pos.y, self.config.brush_radius, to);
             }
         }
     }
@@ -163,6 +201,29 @@ impl Game {
         None
     }
 
+    /// Crops the board to the current selection, if there is one, and then
+    /// clears the selection.
+    pub fn crop_to_selection(&mut self) {
+        let Some(selection) = self.get_selection() else {
+            return;
+        };
+
+        // `end` holds the top left corner and `start` the bottom right one.
+        self.board.crop((
+            selection.end.x,
+            selection.end.y,
+            selection.start.x + 1,
+            selection.start.y + 1,
+        ));
+        self.config.width = self.board.width();
+        self.config.height = self.board.height();
+
+        self.selected_tool = Tool::Selection {
+            start: None,
+            end: None,
+        };
+    }
+
     // TODO: pub fn copy_selection(&mut self)
 }
 
@@ -0,0 +1,17 @@
+//! The game and its automata as a library, so other crates can register
+//! their own automata with [`rules::register`] before running [`game::Game`].
+
+pub mod board;
+pub mod color_schemes;
+pub mod config;
+mod debug_info;
+pub mod game;
+mod hashlife;
+mod input;
+mod notifications;
+mod preview;
+mod recording;
+mod rendering;
+pub mod rules;
+mod ui;
+pub mod utils;
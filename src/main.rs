@@ -1,19 +1,6 @@
 // #![allow(dead_code)]
 
-use game::Game;
-
-mod board;
-mod color_schemes;
-mod config;
-mod debug_info;
-mod game;
-mod input;
-mod notifications;
-mod recording;
-mod rendering;
-mod rules;
-mod ui;
-mod utils;
+use gol2::game::Game;
 
 #[macroquad::main("Game of Life")]
 async fn main() {
@@ -0,0 +1,71 @@
+use egui_macroquad::egui::{self, Rect, Sense, Ui};
+use rand::Rng;
+
+use crate::{
+    board::Board,
+    config::Config,
+    rules::{Rule, CONWAY},
+};
+
+/// Cells along each side of the preview board.
+const SIZE: usize = 64;
+/// Side length of the soup in the middle of the preview board.
+const SOUP: usize = 24;
+/// Pixels per preview cell.
+const CELL: f32 = 3.;
+
+/// A small board running the current rule on a seeded soup, so rules can be
+/// tried without touching the main board. It starts over whenever the rule or
+/// seed changes.
+#[derive(Clone)]
+pub struct RulePreview {
+    board: Board,
+    rule: Rule,
+    seed: u64,
+}
+
+impl Default for RulePreview {
+    fn default() -> Self {
+        Self::new(CONWAY, 0)
+    }
+}
+
+impl RulePreview {
+    fn new(rule: Rule, seed: u64) -> Self {
+        let mut board = Board::new(SIZE, SIZE);
+        board.reseed(seed);
+
+        let start = (SIZE - SOUP) / 2;
+        for y in start..start + SOUP {
+            for x in start..start + SOUP {
+                let alive = board.rng().gen_bool(0.5);
+                board.set_u(x, y, alive);
+            }
+        }
+
+        Self { board, rule, seed }
+    }
+
+    /// Steps the preview once and draws it.
+    pub fn show(&mut self, ui: &mut Ui, config: &Config) {
+        if self.rule != config.rule || self.seed != config.seed {
+            *self = Self::new(config.rule, config.seed);
+        }
+
+        self.board.update(config);
+
+        let side = SIZE as f32 * CELL;
+        let (response, painter) = ui.allocate_painter(egui::vec2(side, side), Sense::hover());
+        let origin = response.rect.min;
+
+        painter.rect_filled(response.rect, 0., config.dead_color.to_egui());
+
+        let everything = (0, 0, SIZE as isize, SIZE as isize);
+        self.board
+            .for_each_colored_tile_in(everything, config, |x, y, color| {
+                let min = origin + egui::vec2(x as f32 * CELL, y as f32 * CELL);
+                let rect = Rect::from_min_size(min, egui::vec2(CELL, CELL));
+                painter.rect_filled(rect, 0., color.to_egui());
+            });
+    }
+}
@@ -5,7 +5,7 @@ use image::{EncodableLayout, RgbaImage};
 use std::fs::{create_dir_all, File};
 use std::path::Path;
 
-use crate::{board::Board, config::Config};
+use crate::{board::Board, config::Config, rules::Neighbourhood, utils::GColor};
 
 pub struct Recording {
     name: String,
@@ -96,7 +96,17 @@ impl Recording {
     }
 
     fn i_width(&self) -> u32 {
-        (self.width * self.upscale) as u32
+        ((self.width * self.upscale) + self.row_shift(0)) as u32
+    }
+
+    /// Pixels row `y` is moved right by. Hexagonal rules are drawn with each
+    /// row half a cell left of the one above, like on screen.
+    fn row_shift(&self, y: usize) -> usize {
+        if self.config.rule.neighbourhood() == Neighbourhood::Hexagonal {
+            (self.height - 1 - y) * self.upscale / 2
+        } else {
+            0
+        }
     }
 
     fn i_height(&self) -> u32 {
@@ -104,37 +114,38 @@ impl Recording {
     }
 
     fn render_frame(&mut self) {
-        let mut buf = RgbaImage::new(self.i_width(), self.i_height());
-        let board = &mut self.board;
-        let config = &self.config;
+        let mut buf = RgbaImage::from_pixel(
+            self.i_width(),
+            self.i_height(),
+            self.config.bg_color.to_img(),
+        );
+        let shifts: Vec<_> = (0..self.height).map(|y| self.row_shift(y)).collect();
+        let upscale = self.upscale;
+
+        let mut put = |x: usize, y: usize, color: GColor| {
+            for dx in 0..upscale {
+                for dy in 0..upscale {
+                    buf.put_pixel(
+                        (shifts[y] + x * upscale + dx) as u32,
+                        (y * upscale + dy) as u32,
+                        color.to_img(),
+                    );
+                }
+            }
+        };
 
         for y in 0..self.height {
             for x in 0..self.width {
-                let tile = board.get(x as isize, y as isize);
-
-                let color = if tile.alive {
-                    config.alive_color
-                } else if tile.heat() != 0 && config.enable_heat {
-                    config.hot_color.blend(
-                        &config.dead_color,
-                        1. - (tile.heat() as f32 / 255.) * self.config.heat_intensity,
-                    )
-                } else {
-                    config.dead_color
-                };
-
-                for dx in 0..self.upscale {
-                    for dy in 0..self.upscale {
-                        buf.put_pixel(
-                            (x * self.upscale + dx) as u32,
-                            (y * self.upscale + dy) as u32,
-                            color.to_img(),
-                        );
-                    }
-                }
+                put(x, y, self.config.dead_color);
             }
         }
 
+        let everything = (0, 0, self.width as isize, self.height as isize);
+        self.board
+            .for_each_colored_tile_in(everything, &self.config, |x, y, color| {
+                put(x as usize, y as usize, color);
+            });
+
         self.frames.push(buf);
         self.board.update(&self.config);
     }
@@ -1,24 +1,29 @@
 use crate::{
     game::Game,
     input::{Selection, Tool},
+    rules::Neighbourhood,
     utils::Vec2I,
 };
 use clipline::Clipline;
 use macroquad::{
-    input::show_mouse, math::Vec2, miniquad::window::screen_size, shapes::draw_rectangle,
+    input::show_mouse,
+    math::{DVec2, Vec2},
+    miniquad::window::screen_size,
+    shapes::{draw_rectangle, draw_triangle},
     window::clear_background,
 };
 
 pub struct Camera {
     pub zoom: f32,
-    pub offset: Vec2,
+    /// Kept in f64 so the view stays precise far away from the origin.
+    pub offset: DVec2,
 }
 
 impl Default for Camera {
     fn default() -> Self {
         Self {
             zoom: 1.,
-            offset: Vec2::ZERO,
+            offset: DVec2::ZERO,
         }
     }
 }
@@ -27,40 +32,32 @@ impl Game {
     pub fn draw(&self) {
         clear_background(self.config.bg_color.to_mq());
 
-        if !self.config.enable_heat {
-            let (x, y) = self.board_to_screen(0, 0);
+        if self.board.is_unbounded() {
+            clear_background(self.config.dead_color.to_mq());
+        } else {
             let (w, h) = self.board_wh_screen();
-            draw_rectangle(x, y, w, h, self.config.dead_color.to_mq());
-        }
 
-        for y in 0..self.board.height() {
-            for x in 0..self.board.width() {
-                let tile = self.board.get(x as isize, y as isize);
-                if self.config.enable_heat {
-                    let color = if tile.alive() {
-                        self.config.alive_color
-                    } else if tile.heat() != 0 {
-                        self.config.hot_color.blend(
-                            &self.config.dead_color,
-                            1. - (tile.heat() as f32 / 255.) * self.config.heat_intensity,
-                        )
-                    } else {
-                        self.config.dead_color
-                    };
-
-                    let (dx, dy) = self.board_to_screen(x as isize, y as isize);
-                    let s = self.tile_size();
-
-                    draw_rectangle(dx, dy, s, s, color.to_mq());
-                } else if tile.alive() {
-                    let (dx, dy) = self.board_to_screen(x as isize, y as isize);
-                    let s = self.tile_size();
-
-                    draw_rectangle(dx, dy, s, s, self.config.alive_color.to_mq());
+            if self.is_hexagonal() {
+                for y in 0..self.board.height() as isize {
+                    let (x, y) = self.board_to_screen(0, y);
+                    draw_rectangle(x, y, w, self.tile_size(), self.config.dead_color.to_mq());
                 }
+            } else {
+                let (x, y) = self.board_to_screen(0, 0);
+                draw_rectangle(x, y, w, h, self.config.dead_color.to_mq());
             }
         }
 
+        let s = self.tile_size();
+
+        self.board
+            .for_each_colored_tile_in(self.visible_rect(), &self.config, |x, y, color| {
+                let (dx, dy) = self.board_to_screen(x, y);
+
+                draw_rectangle(dx, dy, s, s, color.to_mq());
+            });
+
+        self.draw_agents();
         self.draw_line();
         self.draw_selection();
 
@@ -88,16 +85,7 @@ impl Game {
         if let Tool::Line { start, end } = self.selected_tool {
             if let Some(start) = start {
                 if let Some(end) = end {
-                    return Some(Clipline::new(
-                        ((start.x, start.y), (end.x, end.y)),
-                        (
-                            (0, 0),
-                            (
-                                (self.board.width() as isize) - 1,
-                                (self.board.height() as isize) - 1,
-                            ),
-                        ),
-                    )?);
+                    return self.board.line(start.x, start.y, end.x, end.y);
                 }
             }
         }
@@ -121,6 +109,31 @@ impl Game {
         }
     }
 
+    /// A triangle on each agent pointing where it is heading.
+    fn draw_agents(&self) {
+        let s = self.tile_size();
+        let (x0, y0, x1, y1) = self.visible_rect();
+
+        for agent in self.board.agents() {
+            if !(x0..x1).contains(&agent.x) || !(y0..y1).contains(&agent.y) {
+                continue;
+            }
+
+            let (x, y) = self.board_to_screen(agent.x, agent.y);
+            let center = Vec2::new(x + s / 2., y + s / 2.);
+            let (dx, dy) = agent.heading.delta();
+            let forward = Vec2::new(dx as f32, dy as f32) * s * 0.4;
+            let side = forward.perp();
+
+            draw_triangle(
+                center + forward,
+                center - forward + side,
+                center - forward - side,
+                self.config.selection_color.to_mq(),
+            );
+        }
+    }
+
     fn draw_selection(&self) {
         if let Some(Selection {
             start,
@@ -130,6 +143,21 @@ impl Game {
         }) = self.get_selection()
         {
             let s = self.tile_size();
+
+            if self.is_hexagonal() {
+                for y in end.y..end.y + height {
+                    let (sx, sy) = self.board_to_screen(end.x, y);
+                    draw_rectangle(
+                        sx,
+                        sy,
+                        width as f32 * s,
+                        s,
+                        self.config.selection_color.to_mq(),
+                    );
+                }
+                return;
+            }
+
             let (sx, sy) = self.board_to_screen(end.x, end.y);
 
             draw_rectangle(
@@ -142,6 +170,21 @@ impl Game {
         }
     }
 
+    fn is_hexagonal(&self) -> bool {
+        self.config.rule.neighbourhood() == Neighbourhood::Hexagonal
+    }
+
+    /// How far row `y` is drawn to the right, in cells. Hexagonal rules shear
+    /// each row half a cell left of the one above, which puts every cell
+    /// between its six neighbours. The middle row stays in place.
+    fn row_shift(&self, y: isize) -> f64 {
+        if self.is_hexagonal() {
+            (self.board.height() as f64 / 2. - y as f64) / 2.
+        } else {
+            0.
+        }
+    }
+
     pub fn tile_size(&self) -> f32 {
         self.camera.zoom * self.config.tile_size
     }
@@ -158,14 +201,33 @@ impl Game {
         (bw, bh)
     }
 
+    /// Board coordinates covered by the screen, as `(x0, y0, x1, y1)` with
+    /// exclusive upper bounds.
+    pub fn visible_rect(&self) -> (isize, isize, isize, isize) {
+        let (sw, sh) = screen_size();
+        // Sheared rows make the corners the extremes only together.
+        let corners =
+            [(0., 0.), (sw, 0.), (0., sh), (sw, sh)].map(|(x, y)| self.screen_to_board(x, y));
+        let xs = corners.map(|(x, _)| x);
+
+        (
+            *xs.iter().min().unwrap(),
+            corners[0].1,
+            *xs.iter().max().unwrap() + 1,
+            corners[3].1 + 1,
+        )
+    }
+
+    // Both conversions go through f64 so that cells far from the origin still
+    // land on the right pixel.
     pub fn board_to_screen(&self, x: isize, y: isize) -> (f32, f32) {
         let c = &self.camera;
-        let s = self.tile_size();
+        let s = self.tile_size() as f64;
         let (sw, sh) = screen_size();
         let (bw, bh) = self.board_wh_screen();
 
-        let mut x = x as f32;
-        let mut y = y as f32;
+        let mut x = x as f64 + self.row_shift(y);
+        let mut y = y as f64;
 
         x += c.offset.x;
         y += c.offset.y;
@@ -173,20 +235,23 @@ impl Game {
         x *= s;
         y *= s;
 
-        x += (sw - bw) / 2.;
-        y += (sh - bh) / 2.;
+        x += (sw - bw) as f64 / 2.;
+        y += (sh - bh) as f64 / 2.;
 
-        (x, y)
+        (x as f32, y as f32)
     }
 
-    pub fn screen_to_board(&self, mut x: f32, mut y: f32) -> (isize, isize) {
+    pub fn screen_to_board(&self, x: f32, y: f32) -> (isize, isize) {
         let c = &self.camera;
-        let s = self.tile_size();
+        let s = self.tile_size() as f64;
         let (sw, sh) = screen_size();
         let (bw, bh) = self.board_wh_screen();
 
-        x -= (sw - bw) / 2.;
-        y -= (sh - bh) / 2.;
+        let mut x = x as f64;
+        let mut y = y as f64;
+
+        x -= (sw - bw) as f64 / 2.;
+        y -= (sh - bh) as f64 / 2.;
 
         x /= s;
         y /= s;
@@ -194,9 +259,8 @@ impl Game {
         x -= c.offset.x;
         y -= c.offset.y;
 
-        let x = x as isize;
-        let y = y as isize;
+        let y = y.floor() as isize;
 
-        (x, y)
+        ((x - self.row_shift(y)).floor() as isize, y)
     }
 }
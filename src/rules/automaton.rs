@@ -0,0 +1,204 @@
+//! Automata that step the board with arbitrary code instead of a rule
+//! notation, like the generative noise rules.
+//!
+//! They live in a global registry: the built-in ones first, in a fixed order
+//! so `Rule::Custom` indices in saved configs stay valid, then whatever other
+//! crates add with [`register`].
+
+use std::{
+    any::Any,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use super::{builtin, Param, Params, Rule};
+use crate::{
+    board::{Board, Tile},
+    config::Config,
+    utils::GColor,
+};
+
+pub trait Automaton: Send + Sync + 'static {
+    /// Kept on the board between steps. It starts over from [`Automaton::init`]
+    /// whenever the board switches to this automaton.
+    type State: Clone + Send + Sync + 'static;
+
+    fn name(&self) -> &str;
+
+    /// Knobs the automaton reads from the [`Params`] passed to each step.
+    fn params(&self) -> Vec<Param> {
+        Vec::new()
+    }
+
+    fn init(&self, board: &Board) -> Self::State;
+
+    fn step(&self, board: &mut Board, state: &mut Self::State, params: &Params, config: &Config);
+
+    /// Color of a cell, `None` to leave it dead colored. Every cell inside the
+    /// board is asked, not only those with something on them.
+    fn color(
+        &self,
+        state: &Self::State,
+        x: isize,
+        y: isize,
+        tile: Tile,
+        config: &Config,
+    ) -> Option<GColor> {
+        let _ = (state, x, y);
+        config.tile_color(tile)
+    }
+}
+
+/// An automaton's state with its type erased, so boards can hold any.
+pub trait AutomatonState: Any + Send + Sync {
+    fn clone_box(&self) -> Box<dyn AutomatonState>;
+}
+
+impl<T: Any + Clone + Send + Sync> AutomatonState for T {
+    fn clone_box(&self) -> Box<dyn AutomatonState> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn AutomatonState> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// [`Automaton`] with the state type erased, as kept in the registry.
+pub(crate) trait ErasedAutomaton: Send + Sync {
+    fn name(&self) -> &str;
+    fn init(&self, board: &Board) -> Box<dyn AutomatonState>;
+    fn params(&self) -> Vec<Param>;
+    fn step(
+        &self,
+        board: &mut Board,
+        state: &mut dyn AutomatonState,
+        params: &Params,
+        config: &Config,
+    );
+    fn color(
+        &self,
+        state: &dyn AutomatonState,
+        x: isize,
+        y: isize,
+        tile: Tile,
+        config: &Config,
+    ) -> Option<GColor>;
+}
+
+impl<A: Automaton> ErasedAutomaton for A {
+    fn name(&self) -> &str {
+        Automaton::name(self)
+    }
+
+    fn init(&self, board: &Board) -> Box<dyn AutomatonState> {
+        Box::new(Automaton::init(self, board))
+    }
+
+    fn params(&self) -> Vec<Param> {
+        Automaton::params(self)
+    }
+
+    fn step(
+        &self,
+        board: &mut Board,
+        state: &mut dyn AutomatonState,
+        params: &Params,
+        config: &Config,
+    ) {
+        let state = (state as &mut dyn Any).downcast_mut().unwrap();
+        Automaton::step(self, board, state, params, config);
+    }
+
+    fn color(
+        &self,
+        state: &dyn AutomatonState,
+        x: isize,
+        y: isize,
+        tile: Tile,
+        config: &Config,
+    ) -> Option<GColor> {
+        let state = (state as &dyn Any).downcast_ref().unwrap();
+        Automaton::color(self, state, x, y, tile, config)
+    }
+}
+
+static REGISTRY: LazyLock<RwLock<Vec<Arc<dyn ErasedAutomaton>>>> =
+    LazyLock::new(|| RwLock::new(builtin::automata()));
+
+/// Adds an automaton to the registry, returning the rule that runs it.
+pub fn register(automaton: impl Automaton) -> Rule {
+    let mut registry = REGISTRY.write().unwrap();
+    registry.push(Arc::new(automaton));
+
+    Rule::Custom(registry.len() - 1)
+}
+
+/// Every registered automaton with the rule that runs it.
+pub fn automata() -> Vec<(String, Rule)> {
+    REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .enumerate()
+        .map(|(i, automaton)| (automaton.name().to_string(), Rule::Custom(i)))
+        .collect()
+}
+
+/// The automaton behind `Rule::Custom(index)`, if one was registered there.
+pub(crate) fn automaton(index: usize) -> Option<Arc<dyn ErasedAutomaton>> {
+    REGISTRY.read().unwrap().get(index).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts its steps and turns the cell at the count on.
+    struct Counter;
+
+    impl Automaton for Counter {
+        type State = usize;
+
+        fn name(&self) -> &str {
+            "Counter"
+        }
+
+        fn params(&self) -> Vec<Param> {
+            vec![Param::int("Row", 2, 0..=9)]
+        }
+
+        fn init(&self, _board: &Board) -> usize {
+            0
+        }
+
+        fn step(&self, board: &mut Board, state: &mut usize, params: &Params, _: &Config) {
+            board.set(*state as isize, params.int("Row") as isize, true);
+            *state += 1;
+        }
+    }
+
+    #[test]
+    fn registered_automata_keep_state_per_board() {
+        let rule = register(Counter);
+        assert!(automata().contains(&("Counter".to_string(), rule)));
+
+        let config = Config {
+            rule,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let mut board = Board::new(10, 10);
+        for _ in 0..3 {
+            board.update(&config);
+        }
+
+        // Clones carry the state along.
+        let mut copy = board.clone();
+        copy.update(&config);
+
+        assert_eq!(board.live_bounds(), Some((0, 2, 3, 3)));
+        assert_eq!(copy.live_bounds(), Some((0, 2, 4, 3)));
+    }
+}
@@ -0,0 +1,613 @@
+//! The automata that come with the game. Their order fixes their
+//! `Rule::Custom` indices, so new ones go at the end.
+
+use std::sync::Arc;
+
+use rand::prelude::*;
+
+use super::{
+    automaton::{Automaton, ErasedAutomaton},
+    Param, Params, Rule, MAZE,
+};
+use crate::{
+    board::Board,
+    config::{Config, HeatCoupling},
+};
+
+pub(super) fn automata() -> Vec<Arc<dyn ErasedAutomaton>> {
+    vec![
+        Arc::new(FallingStars),
+        Arc::new(MazeCycle),
+        Arc::new(Noise),
+        Arc::new(WorleyLines),
+        Arc::new(Space),
+        Arc::new(PerlinNoise),
+    ]
+}
+
+/// Wraps a stateless step function and its parameters as an automaton.
+macro_rules! stateless {
+    ($automaton: ident, $name: expr, $step: ident, $params: expr) => {
+        struct $automaton;
+
+        impl Automaton for $automaton {
+            type State = ();
+
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn params(&self) -> Vec<Param> {
+                $params
+            }
+
+            fn init(&self, _board: &Board) {}
+
+            fn step(&self, board: &mut Board, _: &mut (), params: &Params, config: &Config) {
+                $step(board, params, config);
+            }
+        }
+    };
+}
+
+stateless!(
+    FallingStars,
+    "Falling stars",
+    falling_stars,
+    vec![
+        Param::int("Spawn one in", 2000, 1..=100_000),
+        Param::int("Kill one in", 40, 1..=10_000),
+        Param::int("Cooling", 10, 0..=255),
+    ]
+);
+stateless!(MazeCycle, "Maze cycle", maze_cycle, vec![]);
+stateless!(
+    Noise,
+    "Noise",
+    noise,
+    vec![Param::int("Flip one in", 500, 1..=100_000)]
+);
+stateless!(WorleyLines, "Worley noise", worley, {
+    let mut params = vec![Param::int("Point spacing", 20, 2..=500)];
+    params.extend(dither_params());
+    params
+});
+stateless!(
+    Space,
+    "Space",
+    space,
+    vec![
+        Param::float("Vertical move chance", 0.5, 0.0..=1.0),
+        Param::int("Drift one in", 10, 1..=10_000),
+    ]
+);
+
+/// Keeps its gradient tables around instead of rebuilding them every step.
+/// They are seeded from the board on the first step.
+struct PerlinNoise;
+
+impl Automaton for PerlinNoise {
+    type State = Option<Perlin4D>;
+
+    fn name(&self) -> &str {
+        "Perlin noise"
+    }
+
+    fn params(&self) -> Vec<Param> {
+        let mut params = vec![
+            Param::float("Scale", 0.005, 0.0005..=0.05),
+            Param::float("Generations per time unit", 300., 1.0..=10_000.0),
+        ];
+        params.extend(dither_params());
+        params
+    }
+
+    fn init(&self, _board: &Board) -> Option<Perlin4D> {
+        None
+    }
+
+    fn step(&self, board: &mut Board, perlin: &mut Option<Perlin4D>, params: &Params, _: &Config) {
+        let perlin = perlin.get_or_insert_with(|| Perlin4D::new(board.rng().gen()));
+        perlin_noise(board, perlin, params);
+    }
+}
+
+/// `gen_ratio` odds from a "one in" parameter.
+fn one_in(params: &Params, name: &str) -> u32 {
+    params.int(name).max(1) as u32
+}
+
+fn falling_stars(board: &mut Board, params: &Params, config: &Config) {
+    let (spawn, kill) = (
+        one_in(params, "Spawn one in"),
+        one_in(params, "Kill one in"),
+    );
+    let cooling = params.int("Cooling") as u8;
+
+    // {
+    //     let s = (board.width() + board.height()) / 10;
+    //
+    //     for _ in 0..s {
+    //         let y = gen_range(0., board.height() as f32) as isize;
+    //         let x = gen_range(0., board.width() as f32) as isize;
+    //
+    //         board.set(x, y, true);
+    //     }
+    // }
+
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            let (x, y) = (x as isize, y as isize);
+            if board.rng().gen_ratio(1, kill) {
+                board.set(x, y, false);
+            }
+        }
+    }
+
+    let old = board.clone();
+
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            let (x, y) = (x as isize, y as isize);
+
+            if board.rng().gen_ratio(1, spawn) {
+                board.set(x, y, true);
+            }
+
+            let t = old.get(x, y);
+
+            if t.alive() {
+                if !old.get(x, y + 1).alive() {
+                    board.set(x, y + 1, true);
+                    board.set(x, y, false);
+                } else {
+                    board.set(x, y, false);
+                }
+            }
+
+            let mut t = board.get(x, y);
+            t.update_heat(config);
+            t.heat = t.heat.saturating_sub(cooling);
+            board.set_heat(x, y, t.heat);
+        }
+    }
+}
+
+fn maze_cycle(board: &mut Board, _params: &Params, config: &Config) {
+    {
+        let x = board.width() / 2;
+        let y = board.height() / 2;
+
+        for y in y - 2..y + 2 {
+            for x in x - 2..x + 2 {
+                if board.rng().gen_ratio(1, 4) {
+                    board.set_u(x, y, true);
+                }
+            }
+        }
+    }
+
+    if let Rule::Rulestring(rule) = MAZE {
+        board.step_heat_coupled(&rule, &HeatCoupling::MAZE_CYCLE, config.threads);
+    } else {
+        panic!("What the fuck");
+    }
+}
+
+fn noise(board: &mut Board, params: &Params, config: &Config) {
+    let s = one_in(params, "Flip one in");
+
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            let mut tile = board.get_u(x, y);
+
+            if tile.alive {
+                tile.alive = !board.rng().gen_ratio(1, s)
+            } else {
+                tile.alive = board.rng().gen_ratio(1, s)
+            }
+
+            tile.update_heat(config);
+            board.set_tile_u(x, y, tile);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Perlin4D {
+    perm: Vec<usize>,
+    grad4: [[f64; 4]; 32],
+}
+
+impl Perlin4D {
+    fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut perm = (0..256).collect::<Vec<_>>();
+        perm.shuffle(&mut rng);
+        perm.extend(perm.clone());
+
+        let mut grad4 = [[0.0; 4]; 32];
+        for g in grad4.iter_mut() {
+            let len = loop {
+                for i in 0..4 {
+                    g[i] = rng.gen_range(-1.0..=1.0);
+                }
+                let len_sq: f64 = g.iter().map(|x| x * x).sum();
+                if len_sq > 0.0 {
+                    break len_sq.sqrt();
+                }
+            };
+
+            for i in 0..4 {
+                g[i] /= len;
+            }
+        }
+
+        Self { perm, grad4 }
+    }
+
+    fn grad4_dot(&self, hash: usize, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        let g = &self.grad4[hash & 31];
+        g[0] * x + g[1] * y + g[2] * z + g[3] * w
+    }
+
+    fn fade(t: f64) -> f64 {
+        t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+    }
+
+    // fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    //     a + t * (b - a)
+    // }
+
+    fn noise(&self, x: f64, y: f64, z: f64, w: f64) -> f64 {
+        let x0 = x.floor() as i32;
+        let y0 = y.floor() as i32;
+        let z0 = z.floor() as i32;
+        let w0 = w.floor() as i32;
+
+        let x = x - x0 as f64;
+        let y = y - y0 as f64;
+        let z = z - z0 as f64;
+        let w = w - w0 as f64;
+
+        let x0 = x0.rem_euclid(256) as usize;
+        let y0 = y0.rem_euclid(256) as usize;
+        let z0 = z0.rem_euclid(256) as usize;
+        let w0 = w0.rem_euclid(256) as usize;
+
+        let u = Self::fade(x);
+        let v = Self::fade(y);
+        let t = Self::fade(z);
+        let s = Self::fade(w);
+
+        let a = self.perm[x0] + y0;
+        // let aa = self.perm[a] + z0;
+        // let ab = self.perm[a + 1] + z0;
+        let b = self.perm[x0 + 1] + y0;
+        // let ba = self.perm[b] + z0;
+        // let bb = self.perm[b + 1] + z0;
+
+        let mut result = 0.0;
+
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..2 {
+                    for l in 0..2 {
+                        let ix = x0 + i;
+                        let iy = y0 + j;
+                        let iz = z0 + k;
+                        let iw = w0 + l;
+
+                        let fx = x - i as f64;
+                        let fy = y - j as f64;
+                        let fz = z - k as f64;
+                        let fw = w - l as f64;
+
+                        let hash = self.perm[self.perm[self.perm[self.perm[ix] + iy] + iz] + iw];
+                        let grad = self.grad4_dot(hash, fx, fy, fz, fw);
+
+                        let weight = (1.0 - i as f64 - u)
+                            * (1.0 - j as f64 - v)
+                            * (1.0 - k as f64 - t)
+                            * (1.0 - l as f64 - s);
+
+                        result += grad * weight;
+                    }
+                }
+            }
+        }
+
+        result * 1.5
+    }
+}
+
+fn generate_noise_grid(
+    perlin: &Perlin4D,
+    width: usize,
+    height: usize,
+    scale: f64,
+    z: f64,
+    w: f64,
+) -> Vec<Vec<f64>> {
+    let mut grid = vec![vec![0.0; width]; height];
+
+    for y in 0..height {
+        for x in 0..width {
+            let nx = x as f64 * scale;
+            let ny = y as f64 * scale;
+            grid[y][x] = perlin.noise(nx, ny, z, w);
+        }
+    }
+
+    grid
+}
+
+/// Perlin noise over the board area, roughly between -1 and 1, for seeding
+/// other rules.
+pub(crate) fn perlin_grid(seed: u64, width: usize, height: usize, scale: f64) -> Vec<Vec<f64>> {
+    generate_noise_grid(&Perlin4D::new(seed), width, height, scale, 0., 0.)
+}
+
+// Used to be wall-clock seconds / 5, which at 60 generations a second
+// is the same speed.
+fn get_time(generation: u64, generations_per_unit: f64) -> f64 {
+    generation as f64 / generations_per_unit
+}
+
+fn perlin_noise(mut board: &mut Board, perlin: &Perlin4D, params: &Params) {
+    // let old = board.clone();
+
+    // board.clear();
+
+    let width = board.width();
+    let height = board.height();
+    let scale = params.float("Scale");
+    let z = 0.;
+    let w = get_time(
+        board.generation(),
+        params.float("Generations per time unit"),
+    );
+    let thresholds = dither_thresholds(params);
+
+    let noise_grid = generate_noise_grid(perlin, width, height, scale, z, w);
+
+    for x in 0..board.width() {
+        for y in 0..board.height() {
+            let noise_value = noise_grid[y][x].abs();
+
+            dither(&mut board, x, y, noise_value, &thresholds);
+
+            // let t = board.get_mut_u(x, y);
+            // if t.alive {
+            //     t.heat = 255;
+            // } else {
+            //     t.heat = t.heat.saturating_sub(1);
+            // }
+            //
+            // if t.heat < 235 {
+            //     t.heat = 0;
+            // }
+        }
+    }
+}
+
+/// Values at which dithering moves to the next denser pattern.
+const DITHER_LEVELS: [(&str, f64); 6] = [
+    ("Dither level 1", 0.1),
+    ("Dither level 2", 0.2),
+    ("Dither level 3", 0.3),
+    ("Dither level 4", 0.4),
+    ("Dither level 5", 0.52),
+    ("Dither level 6", 0.64),
+];
+
+fn dither_params() -> impl Iterator<Item = Param> {
+    DITHER_LEVELS
+        .iter()
+        .map(|&(name, default)| Param::float(name, default, 0.0..=1.0))
+}
+
+/// The dither levels in order, whatever order the sliders were left in.
+fn dither_thresholds(params: &Params) -> [f64; 6] {
+    let mut thresholds = DITHER_LEVELS.map(|(name, _)| params.float(name));
+    thresholds.sort_by(f64::total_cmp);
+    thresholds
+}
+
+/// Sets a cell to the dither pattern picked by how many thresholds `value`
+/// reaches, from all dead to all alive.
+fn dither(board: &mut Board, x: usize, y: usize, value: f64, thresholds: &[f64; 6]) {
+    let alive = match thresholds.iter().filter(|&&t| value >= t).count() {
+        0 => false,
+        1 => x % 3 == 0 && y % 3 == 0,
+        2 => x % 2 == 0 && y % 2 == 0,
+        3 => x % 2 == y % 2,
+        4 => !(x % 2 == 0 && y % 2 == 0),
+        5 => !(x % 3 == 0 && y % 3 == 0),
+        _ => true,
+    };
+
+    board.set_u(x, y, alive);
+}
+
+fn worley(mut board: &mut Board, params: &Params, _config: &Config) {
+    board.clear();
+
+    // about one point in every spacing x spacing area.
+    let spacing = params.int("Point spacing") as usize;
+    let thresholds = dither_thresholds(params);
+    let cells = worley_cells(board, spacing);
+    let grid: Vec<_> = cells.iter().map(|&(_, point)| point).collect();
+
+    for (i, &(min_dist, _)) in cells.iter().enumerate() {
+        let (x, y) = (i % board.width(), i / board.width());
+
+        // board.get_mut_u(x, y).heat = 255 - (min_dist * 10.) as u8;
+        dither(
+            board,
+            x,
+            y,
+            (min_dist as f64 / 500.).clamp(0., 1.),
+            &thresholds,
+        );
+    }
+
+    for x in 0..board.width() {
+        for y in 0..board.height() {
+            let point = grid[y * board.width() + x];
+
+            {
+                let i = (y + 1) * board.width() + x;
+
+                if x < board.width() - 1 && grid.len() > i && grid[i] != point {
+                    board.set_u(x + 1, y, true);
+                }
+            }
+
+            {
+                let i = y * board.width() + x + 1;
+
+                if y < board.height() - 1 && grid.len() > i && grid[i] != point {
+                    board.set_u(x + 1, y, true);
+                }
+            }
+        }
+    }
+}
+
+/// Scatters about one point in every `spacing` by `spacing` area over the
+/// board, and at least one, and finds the squared distance to the closest point and the point
+/// itself for every cell, row by row.
+pub(crate) fn worley_cells(board: &mut Board, spacing: usize) -> Vec<(f32, (usize, usize))> {
+    let (width, height) = (board.width(), board.height());
+    let num_points = ((width * height) / spacing.pow(2)).max(1);
+    let points: Vec<_> = (0..num_points)
+        .map(|_| {
+            let x = board.rng().gen_range(0..width);
+            let y = board.rng().gen_range(0..height);
+
+            (x, y)
+        })
+        .collect();
+
+    let mut cells = Vec::with_capacity(width * height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut min_dist = f32::MAX;
+            let mut closest_point = (0, 0);
+
+            for &point in points.iter() {
+                let dist = (x as f32 - point.0 as f32).abs().powi(2)
+                    + (y as f32 - point.1 as f32).abs().powi(2);
+
+                if dist < min_dist {
+                    min_dist = dist;
+                    closest_point = point;
+                }
+            }
+
+            cells.push((min_dist, closest_point));
+        }
+    }
+
+    cells
+}
+
+fn space(board: &mut Board, params: &Params, config: &Config) {
+    let vertical = params.float("Vertical move chance");
+    let drift = one_in(params, "Drift one in");
+
+    let height = board.height();
+    let width = board.width();
+
+    let mut moves = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let tile = board.get_u(x, y);
+            if tile.alive {
+                if board.rng().gen_bool(vertical) {
+                    if y + 1 < height
+                        && !board.get_u(x, y + 1).alive
+                        && y > 0
+                        && (board.get_u(x, y - 1).alive || board.rng().gen_ratio(1, drift))
+                    {
+                        moves.push((x, y, x, y + 1));
+                    } else if y + 1 < height
+                        && (board.get_u(x, y + 1).alive || board.rng().gen_ratio(1, drift))
+                        && y > 0
+                        && !board.get_u(x, y - 1).alive
+                    {
+                        moves.push((x, y, x, y - 1));
+                    }
+                } else {
+                    if x + 1 < width
+                        && !board.get_u(x + 1, y).alive
+                        && x > 0
+                        && (board.get_u(x - 1, y).alive || board.rng().gen_ratio(1, drift))
+                    {
+                        moves.push((x, y, x + 1, y));
+                    } else if x + 1 < width
+                        && (board.get_u(x + 1, y).alive || board.rng().gen_ratio(1, drift))
+                        && x > 0
+                        && !board.get_u(x - 1, y).alive
+                    {
+                        moves.push((x, y, x - 1, y));
+                    }
+                }
+            }
+        }
+    }
+
+    for (from_x, from_y, to_x, to_y) in moves {
+        let mut from_tile = board.get_u(from_x, from_y);
+        from_tile.alive = false;
+        from_tile.update_heat(config);
+        board.set_tile_u(from_x, from_y, from_tile);
+
+        let mut to_tile = board.get_u(to_x, to_y);
+        to_tile.alive = true;
+        to_tile.update_heat(config);
+        board.set_tile_u(to_x, to_y, to_tile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::PERLIN_NOISE;
+
+    #[test]
+    fn perlin_noise_follows_the_seed() {
+        let config = Config {
+            rule: PERLIN_NOISE,
+            enable_heat: false,
+            ..Default::default()
+        };
+        let run = |seed| {
+            let mut board = Board::new(48, 48);
+            board.reseed(seed);
+            board.update(&config);
+            serde_json::to_string(&board).unwrap()
+        };
+
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+    }
+
+    #[test]
+    fn dithering_handles_any_thresholds() {
+        let mut board = Board::new(6, 6);
+        let thresholds = [0.9, 0.1, 0.5, 0.3, 0.7, 0.8];
+
+        dither(&mut board, 1, 1, 1., &thresholds);
+        assert!(board.is_alive(1, 1));
+        dither(&mut board, 1, 1, 0.05, &thresholds);
+        assert!(!board.is_alive(1, 1));
+
+        let cells = worley_cells(&mut board, 500);
+        assert!(cells.iter().all(|&(dist, _)| dist < f32::MAX));
+    }
+}
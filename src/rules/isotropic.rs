@@ -0,0 +1,279 @@
+//! Isotropic non-totalistic rules in Hensel notation, like `B2-a/S12` or
+//! `B3/S23-a4i`.
+//!
+//! A digit alone stands for every arrangement of that many neighbours. Letters
+//! after it pick arrangements up to rotation and reflection, and a `-` before
+//! the letters picks every arrangement except those.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Bit `k` of a neighbourhood is set when the `k`th of these neighbours is
+/// alive, reading order from the top left.
+pub const NEIGHBOURS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Letters for 1 to 4 neighbours, in the order Golly uses. 5 to 7 neighbours
+/// use the letters of 3 to 1, for the complemented arrangement.
+const LETTERS: [&str; 4] = ["ce", "ceaikn", "ceaiknjqry", "ceaiknjqrtwyz"];
+
+/// One arrangement for each letter in [`LETTERS`], as NW 1, N 2, NE 4, W 8,
+/// E 16, SW 32, S 64, SE 128.
+const REPRESENTATIVES: [&[u8]; 4] = [
+    &[1, 2],
+    &[5, 10, 3, 24, 17, 36],
+    &[37, 26, 11, 7, 50, 13, 14, 38, 25, 49],
+    &[165, 90, 15, 29, 51, 39, 58, 54, 27, 53, 57, 46, 60],
+];
+
+/// A 256 entry birth and survival table, indexed by neighbourhood.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Isotropic {
+    pub birth: [u64; 4],
+    pub survive: [u64; 4],
+}
+
+impl Isotropic {
+    pub fn next(&self, alive: bool, neighbours: u8) -> bool {
+        get(if alive { &self.survive } else { &self.birth }, neighbours)
+    }
+}
+
+/// Every rotation and reflection of a neighbourhood: four quarter turns,
+/// then the same turns of its mirror image.
+fn symmetries(neighbours: u8) -> [u8; 8] {
+    let mut out = [neighbours; 8];
+    for i in 1..8 {
+        out[i] = if i == 4 {
+            transform(neighbours, |(x, y)| (-x, y))
+        } else {
+            transform(out[i - 1], |(x, y)| (-y, x))
+        };
+    }
+
+    out
+}
+
+fn transform(neighbours: u8, f: fn((isize, isize)) -> (isize, isize)) -> u8 {
+    (0..8)
+        .filter(|k| neighbours >> k & 1 == 1)
+        .map(|k| {
+            let position = f(NEIGHBOURS[k]);
+            1 << NEIGHBOURS.iter().position(|&p| p == position).unwrap()
+        })
+        .fold(0, |acc, bit| acc | bit)
+}
+
+/// The neighbourhoods `count` live neighbours arranged as `letter` can take.
+fn arrangements(count: usize, letter: char) -> Option<Vec<u8>> {
+    let (base, complement) = if count <= 4 {
+        (count, false)
+    } else {
+        (8 - count, true)
+    };
+    let index = LETTERS.get(base.checked_sub(1)?)?.find(letter)?;
+    let representative = REPRESENTATIVES[base - 1][index];
+    let representative = if complement {
+        !representative
+    } else {
+        representative
+    };
+
+    let mut all = symmetries(representative).to_vec();
+    all.sort_unstable();
+    all.dedup();
+    Some(all)
+}
+
+fn letters(count: usize) -> &'static str {
+    match count {
+        1..=4 => LETTERS[count - 1],
+        5..=7 => LETTERS[7 - count],
+        _ => "",
+    }
+}
+
+fn set(table: &mut [u64; 4], neighbours: u8) {
+    table[neighbours as usize / 64] |= 1 << (neighbours % 64);
+}
+
+fn get(table: &[u64; 4], neighbours: u8) -> bool {
+    table[neighbours as usize / 64] >> (neighbours % 64) & 1 == 1
+}
+
+/// Parses the counts and letters after a `B` or `S`.
+fn parse_half(text: &str, table: &mut [u64; 4]) -> Result<()> {
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let Some(count) = c.to_digit(10).filter(|&d| d <= 8).map(|d| d as usize) else {
+            bail!("Expected a neighbour count from 0 to 8, got '{c}'");
+        };
+
+        let negate = chars.next_if_eq(&'-').is_some();
+        let mut picked = String::new();
+        while let Some(letter) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            if !letters(count).contains(letter) {
+                bail!("'{letter}' is not a valid arrangement of {count} neighbours");
+            }
+            picked.push(letter);
+        }
+
+        if negate && picked.is_empty() {
+            bail!("Expected letters after '{count}-'");
+        }
+
+        let included = letters(count)
+            .chars()
+            .filter(|&letter| picked.contains(letter) != negate);
+
+        if picked.is_empty() {
+            for neighbours in 0..=255u8 {
+                if neighbours.count_ones() as usize == count {
+                    set(table, neighbours);
+                }
+            }
+        } else {
+            for letter in included {
+                for neighbours in arrangements(count, letter).unwrap() {
+                    set(table, neighbours);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn fmt_half(f: &mut fmt::Formatter<'_>, table: &[u64; 4]) -> fmt::Result {
+    for count in 0..=8 {
+        let all = letters(count);
+        let picked: String = all
+            .chars()
+            .filter(|&letter| {
+                arrangements(count, letter)
+                    .unwrap()
+                    .iter()
+                    .all(|&n| get(table, n))
+            })
+            .collect();
+        let any = (0..=255u8).any(|n| n.count_ones() as usize == count && get(table, n));
+
+        if !any {
+            continue;
+        }
+
+        if picked.len() == all.len() {
+            write!(f, "{count}")?;
+        } else if picked.len() * 2 <= all.len() {
+            write!(f, "{count}{picked}")?;
+        } else {
+            let missing: String = all.chars().filter(|&l| !picked.contains(l)).collect();
+            write!(f, "{count}-{missing}")?;
+        }
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Isotropic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        fmt_half(f, &self.birth)?;
+        write!(f, "/S")?;
+        fmt_half(f, &self.survive)
+    }
+}
+
+impl FromStr for Isotropic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let Some((birth, survive)) = s.split_once('/') else {
+            bail!("Expected a rule like B3/S23-a4i");
+        };
+        let (Some(birth), Some(survive)) = (birth.strip_prefix('b'), survive.strip_prefix('s'))
+        else {
+            bail!("Expected the birth part to start with B and the survival part with S");
+        };
+
+        let mut rule = Self {
+            birth: [0; 4],
+            survive: [0; 4],
+        };
+        parse_half(birth, &mut rule.birth)?;
+        parse_half(survive, &mut rule.survive)?;
+
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_split_every_count_into_its_classes() {
+        for count in 1..=7 {
+            let mut seen = vec![];
+
+            for letter in letters(count).chars() {
+                let class = arrangements(count, letter).unwrap();
+                assert!(class.iter().all(|n| n.count_ones() as usize == count));
+
+                // A class is closed under symmetry and shares nothing with
+                // the other classes.
+                for &n in &class {
+                    assert!(symmetries(n).iter().all(|s| class.contains(s)));
+                    assert!(!seen.contains(&n), "{count}{letter}");
+                }
+                seen.extend(class);
+            }
+
+            let total = (0..=255u8)
+                .filter(|n| n.count_ones() as usize == count)
+                .count();
+            assert_eq!(seen.len(), total, "{count}");
+        }
+    }
+
+    #[test]
+    fn parses_and_prints_hensel_notation() {
+        let rule: Isotropic = "B3/S23-a4i".parse().unwrap();
+
+        // A live cell with its N, NE and W neighbours is the 3j arrangement,
+        // which isn't excluded.
+        assert!(rule.next(true, 2 | 4 | 8));
+        // NW, N and W is 3a.
+        assert!(!rule.next(true, 1 | 2 | 8));
+        // Every arrangement of 2 survives.
+        assert!(rule.next(true, 2 | 64));
+        // NW, NE, W and E is 4i, but N, W, E and S is 4e.
+        assert!(rule.next(true, 1 | 4 | 8 | 16));
+        assert!(!rule.next(true, 2 | 8 | 16 | 64));
+        assert!(rule.next(false, 1 | 2 | 4));
+        assert!(!rule.next(false, 1 | 2));
+
+        assert_eq!(rule.to_string(), "B3/S23-a4i");
+        assert_eq!(
+            "b2-a/s12".parse::<Isotropic>().unwrap().to_string(),
+            "B2-a/S12"
+        );
+        assert_eq!("B3/S23".parse::<Isotropic>().unwrap().to_string(), "B3/S23");
+
+        for bad in ["B3S23", "B9/S23", "B1a/S2", "B3/S2-", "S23/B3", "B3/S2x"] {
+            assert!(bad.parse::<Isotropic>().is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Continuous rules in the style of Lenia and SmoothLife. Cells hold a value
+//! between 0 and 1; each step the values around a cell are averaged through
+//! a kernel, and the cell grows or shrinks by `dt` times the growth of that
+//! average, which peaks at `mu` and falls off over `sigma`.
+//!
+//! Written like `Lenia R13 mu0.15 sigma0.015 dt0.1`, or `SmoothLife ...` for
+//! the flat ring kernel.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+/// Largest supported kernel radius.
+pub const MAX_RADIUS: u8 = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, Display)]
+pub enum KernelShape {
+    /// Lenia's smooth bump, heaviest halfway out.
+    Lenia,
+    /// SmoothLife's flat ring, from a third of the radius outwards.
+    SmoothLife,
+}
+
+pub const KERNEL_SHAPES: &[KernelShape] = &[KernelShape::Lenia, KernelShape::SmoothLife];
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Lenia {
+    pub radius: u8,
+    pub mu: f32,
+    pub sigma: f32,
+    pub dt: f32,
+    pub kernel: KernelShape,
+}
+
+impl Lenia {
+    /// Offsets within the radius with their weights, which add up to 1.
+    pub fn kernel(&self) -> Vec<(isize, isize, f32)> {
+        let r = self.radius.max(1) as isize;
+        let mut kernel = vec![];
+
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let d = ((dx * dx + dy * dy) as f32).sqrt() / r as f32;
+
+                let weight = match self.kernel {
+                    KernelShape::Lenia if d > 0. && d < 1. => (4. - 1. / (d * (1. - d))).exp(),
+                    KernelShape::SmoothLife if (1. / 3. ..=1.).contains(&d) => 1.,
+                    _ => 0.,
+                };
+
+                if weight > 0. {
+                    kernel.push((dx, dy, weight));
+                }
+            }
+        }
+
+        let total: f32 = kernel.iter().map(|(_, _, w)| w).sum();
+        for (_, _, w) in &mut kernel {
+            *w /= total;
+        }
+
+        kernel
+    }
+
+    /// How much a cell grows for a kernel average `u`, between -1 and 1.
+    pub fn growth(&self, u: f32) -> f32 {
+        2. * (-(u - self.mu).powi(2) / (2. * self.sigma.powi(2))).exp() - 1.
+    }
+}
+
+impl FromStr for Lenia {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut words = s.split_whitespace();
+
+        let kernel = match words.next().map(str::to_ascii_lowercase).as_deref() {
+            Some("lenia") => KernelShape::Lenia,
+            Some("smoothlife") => KernelShape::SmoothLife,
+            _ => bail!("Expected a rule like Lenia R13 mu0.15 sigma0.015 dt0.1"),
+        };
+        let (mut radius, mut mu, mut sigma, mut dt) = (None, None, None, None);
+
+        for word in words {
+            let lower = word.to_ascii_lowercase();
+            let split = lower
+                .find(|c: char| c.is_ascii_digit() || c == '.')
+                .with_context(|| format!("Expected a value in '{word}'"))?;
+            let (name, value) = lower.split_at(split);
+
+            match name {
+                "r" => radius = Some(value.parse::<u8>().context("Invalid radius")?),
+                "mu" => mu = Some(value.parse::<f32>().context("Invalid mu")?),
+                "sigma" => sigma = Some(value.parse::<f32>().context("Invalid sigma")?),
+                "dt" => dt = Some(value.parse::<f32>().context("Invalid dt")?),
+                _ => bail!("Unknown setting '{word}'"),
+            }
+        }
+
+        let (Some(radius), Some(mu), Some(sigma), Some(dt)) = (radius, mu, sigma, dt) else {
+            bail!("Expected R, mu, sigma and dt");
+        };
+        if !(1..=MAX_RADIUS).contains(&radius) {
+            bail!("The radius must be between 1 and {MAX_RADIUS}");
+        }
+        if sigma <= 0. || dt <= 0. || dt > 1. {
+            bail!("Sigma must be above 0 and dt between 0 and 1");
+        }
+
+        Ok(Self {
+            radius,
+            mu,
+            sigma,
+            dt,
+            kernel,
+        })
+    }
+}
+
+impl fmt::Display for Lenia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} R{} mu{} sigma{} dt{}",
+            self.kernel, self.radius, self.mu, self.sigma, self.dt
+        )
+    }
+}
@@ -0,0 +1,172 @@
+//! Larger than Life: life-like rules over a neighbourhood of any range, with
+//! birth and survival given as ranges of neighbour counts.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Largest supported range.
+pub const MAX_RANGE: u8 = 50;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum LtlShape {
+    /// Every cell within the range along both axes.
+    Moore,
+    /// Cells within the range in Manhattan distance.
+    VonNeumann,
+}
+
+/// A rule in the `R5,C0,M1,S34..58,B34..45,NM` notation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct LargerThanLife {
+    pub range: u8,
+    /// Number of states. Anything below 3 means cells are simply dead or
+    /// alive, otherwise dying cells decay like in Generations rules.
+    pub states: u8,
+    /// Whether a live cell counts itself as a neighbour.
+    pub middle: bool,
+    /// Inclusive neighbour count range for a live cell to survive.
+    pub survive: (u16, u16),
+    /// Inclusive neighbour count range for a dead cell to be born.
+    pub birth: (u16, u16),
+    pub shape: LtlShape,
+}
+
+impl LargerThanLife {
+    pub fn survives(&self, n: u32) -> bool {
+        (self.survive.0 as u32..=self.survive.1 as u32).contains(&n)
+    }
+
+    pub fn is_born(&self, n: u32) -> bool {
+        (self.birth.0 as u32..=self.birth.1 as u32).contains(&n)
+    }
+}
+
+impl fmt::Display for LargerThanLife {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.range,
+            self.states,
+            self.middle as u8,
+            self.survive.0,
+            self.survive.1,
+            self.birth.0,
+            self.birth.1,
+            match self.shape {
+                LtlShape::Moore => 'M',
+                LtlShape::VonNeumann => 'N',
+            }
+        )
+    }
+}
+
+impl FromStr for LargerThanLife {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_uppercase();
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+
+        let [r, c, m, survive, birth, n] = parts[..] else {
+            bail!("Expected six comma separated parts, like R5,C0,M1,S34..58,B34..45,NM");
+        };
+
+        let field = |part: &'static str, value: &str, prefix: char| -> Result<u16> {
+            let Some(digits) = value.strip_prefix(prefix) else {
+                bail!("The {part} should start with '{prefix}', got '{value}'");
+            };
+
+            digits
+                .parse()
+                .with_context(|| format!("Invalid {part} '{digits}'"))
+        };
+
+        let range = |part: &'static str, value: &str, prefix: char| -> Result<(u16, u16)> {
+            let Some(range) = value.strip_prefix(prefix) else {
+                bail!("The {part} range should start with '{prefix}', got '{value}'");
+            };
+            let Some((min, max)) = range.split_once("..") else {
+                bail!("The {part} range should look like {prefix}min..max, got '{value}'");
+            };
+
+            let min = min
+                .parse()
+                .with_context(|| format!("Invalid {part} minimum '{min}'"))?;
+            let max = max
+                .parse()
+                .with_context(|| format!("Invalid {part} maximum '{max}'"))?;
+
+            if min > max {
+                bail!("The {part} range {min}..{max} is empty");
+            }
+
+            Ok((min, max))
+        };
+
+        let rule = Self {
+            range: match field("range", r, 'R')? {
+                r @ 1.. if r <= MAX_RANGE as u16 => r as u8,
+                r => bail!("The range must be between 1 and {MAX_RANGE}, got {r}"),
+            },
+            states: match field("state count", c, 'C')? {
+                c @ ..=255 => c as u8,
+                c => bail!("At most 255 states are supported, got {c}"),
+            },
+            middle: match field("middle flag", m, 'M')? {
+                0 => false,
+                1 => true,
+                m => bail!("The middle flag must be M0 or M1, got M{m}"),
+            },
+            survive: range("survival", survive, 'S')?,
+            birth: range("birth", birth, 'B')?,
+            shape: match n {
+                "NM" => LtlShape::Moore,
+                "NN" => LtlShape::VonNeumann,
+                _ => bail!("The neighbourhood must be NM or NN, got '{n}'"),
+            },
+        };
+
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_prints_the_standard_notation() {
+        let bosco: LargerThanLife = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+
+        assert_eq!(
+            bosco,
+            LargerThanLife {
+                range: 5,
+                states: 0,
+                middle: true,
+                survive: (34, 58),
+                birth: (34, 45),
+                shape: LtlShape::Moore,
+            }
+        );
+        assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+
+        let lower: LargerThanLife = " r10,c3,m0,s1..2,b3..3,nn ".parse().unwrap();
+        assert_eq!(lower.shape, LtlShape::VonNeumann);
+        assert_eq!((lower.range, lower.states, lower.middle), (10, 3, false));
+
+        for bad in [
+            "R5,C0,M1,S34..58,B34..45",
+            "R0,C0,M1,S1..2,B1..2,NM",
+            "R5,C0,M2,S1..2,B1..2,NM",
+            "R5,C0,M1,S9..2,B1..2,NM",
+            "R5,C0,M1,S1-2,B1..2,NM",
+            "R5,C0,M1,S1..2,B1..2,NX",
+        ] {
+            assert!(bad.parse::<LargerThanLife>().is_err(), "{bad}");
+        }
+    }
+}
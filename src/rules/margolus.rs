@@ -0,0 +1,74 @@
+//! Block rules on the Margolus neighbourhood: the board is split into 2x2
+//! blocks, each replaced as a whole by its entry in a table, and the split is
+//! shifted by one cell diagonally every other generation.
+//!
+//! A block is numbered by its live cells, 1 for the north west one, 2 north
+//! east, 4 south west and 8 south east. Rules are written like MCell does,
+//! `MS,D` followed by the sixteen new blocks separated by semicolons.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Margolus {
+    /// The block each block turns into.
+    pub table: [u8; 16],
+}
+
+impl Margolus {
+    /// Whether every block maps to a different one, so the rule can be run
+    /// backwards.
+    pub fn is_reversible(&self) -> bool {
+        let mut seen = [false; 16];
+        self.table
+            .iter()
+            .all(|&b| !std::mem::replace(&mut seen[b as usize], true))
+    }
+}
+
+impl FromStr for Margolus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s: String = s.split_whitespace().collect();
+
+        if !s.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("ms,d")) {
+            bail!("Expected a block rule like MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15");
+        }
+        let blocks = &s[4..];
+
+        let mut table = [0; 16];
+        let mut count = 0;
+
+        for (i, block) in blocks.split(';').enumerate() {
+            let Ok(block) = block.parse::<u8>() else {
+                bail!("Expected a block number, got '{block}'");
+            };
+            if block > 15 {
+                bail!("Blocks go up to 15, got {block}");
+            }
+            if i >= 16 {
+                bail!("Expected 16 blocks, got more");
+            }
+
+            table[i] = block;
+            count += 1;
+        }
+
+        if count != 16 {
+            bail!("Expected 16 blocks, got {count}");
+        }
+
+        Ok(Self { table })
+    }
+}
+
+impl fmt::Display for Margolus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let blocks: Vec<String> = self.table.iter().map(u8::to_string).collect();
+
+        write!(f, "MS,D{}", blocks.join(";"))
+    }
+}
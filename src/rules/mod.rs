@@ -0,0 +1,349 @@
+use std::{f64::consts, ops::RangeBounds};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIs};
+
+pub use automaton::{automata, register, Automaton, AutomatonState};
+pub use gray_scott::{GrayScott, Seeding, SEEDINGS};
+pub use isotropic::{Isotropic, NEIGHBOURS};
+pub use lenia::{KernelShape, Lenia, KERNEL_SHAPES, MAX_RADIUS};
+pub use ltl::{LargerThanLife, LtlShape};
+pub use margolus::Margolus;
+pub use params::{Param, ParamKind, ParamValue, Params};
+pub use sand::{Material, MATERIALS};
+pub use species::{species_of, species_tile, Species, MAX_SPECIES};
+pub use stochastic::Stochastic;
+pub use turmite::{Heading, Turmite, Turn};
+pub use wireworld::{WireworldCell, WIREWORLD_CELLS};
+
+pub(crate) use automaton::automaton;
+pub(crate) use builtin::{perlin_grid, worley_cells};
+
+mod automaton;
+mod builtin;
+mod gray_scott;
+mod isotropic;
+mod lenia;
+mod ltl;
+mod margolus;
+mod params;
+mod parse;
+mod sand;
+mod species;
+mod stochastic;
+mod turmite;
+pub mod wireworld;
+
+macro_rules! rulestring {
+    ($s: expr) => {{
+        let bytes = $s.as_bytes();
+        let mut i = 0;
+        let mut survive = [false; 9];
+        let mut spawn = [false; 9];
+        let mut neighbourhood = Neighbourhood::Moore;
+        let mut parsing_survive = true;
+
+        while i < bytes.len() {
+            match bytes[i] as char {
+                '/' => {
+                    parsing_survive = false;
+                    i += 1;
+                    continue;
+                }
+                c @ '0'..='8' => {
+                    let idx = char_to_digit!(c);
+                    if parsing_survive {
+                        survive[idx] = true;
+                    } else {
+                        spawn[idx] = true;
+                    }
+                }
+                'H' => neighbourhood = Neighbourhood::Hexagonal,
+                'V' => neighbourhood = Neighbourhood::VonNeumann,
+                _ => panic!("Invalid character in rulestring"),
+            }
+            i += 1;
+        }
+
+        Rule::Rulestring(Rulestring {
+            survive,
+            spawn,
+            neighbourhood,
+        })
+    }};
+}
+
+/// A Generations rule from its `S/B` part and number of states.
+macro_rules! generations {
+    ($s: expr, $states: expr) => {{
+        let Rule::Rulestring(life) = rulestring!($s) else {
+            unreachable!()
+        };
+
+        Rule::Generations(Generations {
+            life,
+            states: $states,
+        })
+    }};
+}
+
+/// A multi-species rule from its `S/B` part and number of species.
+macro_rules! species {
+    ($s: expr, $species: expr) => {{
+        let Rule::Rulestring(life) = rulestring!($s) else {
+            unreachable!()
+        };
+
+        Rule::Species(Species {
+            life,
+            species: $species,
+        })
+    }};
+}
+
+macro_rules! char_to_digit {
+    ($c: expr) => {
+        match $c {
+            '0' => 0,
+            '1' => 1,
+            '2' => 2,
+            '3' => 3,
+            '4' => 4,
+            '5' => 5,
+            '6' => 6,
+            '7' => 7,
+            '8' => 8,
+            '9' => 9,
+            _ => panic!("Invalid digit"),
+        }
+    };
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Copy, EnumIs)]
+pub enum Rule {
+    Rulestring(Rulestring),
+    Stochastic(Stochastic),
+    Margolus(Margolus),
+    Generations(Generations),
+    LargerThanLife(LargerThanLife),
+    Isotropic(Isotropic),
+    Species(Species),
+    Lenia(Lenia),
+    GrayScott(GrayScott),
+    Wireworld,
+    FallingSand,
+    Turmite(Turmite),
+    Custom(usize),
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Copy)]
+pub struct Rulestring {
+    pub survive: [bool; 9],
+    pub spawn: [bool; 9],
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
+}
+
+/// Which cells around a cell count as its neighbours, written as a suffix on
+/// the rulestring like Golly does: none for Moore, `H` or `V`.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize, Display)]
+pub enum Neighbourhood {
+    /// All eight surrounding cells.
+    #[default]
+    Moore,
+    /// Six cells, leaving out the north east and south west corners. Rows are
+    /// drawn sheared by half a cell each so the six sit around the cell.
+    Hexagonal,
+    /// The four orthogonal cells.
+    #[strum(to_string = "Von Neumann")]
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    /// The counted cells, with bit `k` standing for `NEIGHBOURS[k]`.
+    pub fn mask(self) -> u8 {
+        match self {
+            Neighbourhood::Moore => 0b1111_1111,
+            Neighbourhood::Hexagonal => 0b1101_1011,
+            Neighbourhood::VonNeumann => 0b0101_1010,
+        }
+    }
+}
+
+impl Rule {
+    /// Number of states for rules where dying cells decay through extra
+    /// states, `None` for every other rule.
+    pub fn decay_states(&self) -> Option<u8> {
+        match self {
+            Rule::Generations(rule) => Some(rule.states),
+            Rule::LargerThanLife(rule) if rule.states > 2 => Some(rule.states),
+            _ => None,
+        }
+    }
+
+    /// Whether the rule keeps more in a cell than unbounded boards store or
+    /// needs the board to end, so it leaves unbounded boards as they are.
+    pub fn needs_bounds(&self) -> bool {
+        matches!(
+            self,
+            Rule::Generations(_)
+                | Rule::Species(_)
+                | Rule::Lenia(_)
+                | Rule::GrayScott(_)
+                | Rule::Wireworld
+                | Rule::FallingSand
+        )
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        match self {
+            Rule::Rulestring(rule) => rule.neighbourhood,
+            Rule::Generations(rule) => rule.life.neighbourhood,
+            Rule::Stochastic(rule) => rule.neighbourhood,
+            _ => Neighbourhood::Moore,
+        }
+    }
+}
+
+/// A life-like rule where dying cells take `states - 2` more generations to
+/// become dead. State 0 is dead, 1 alive and the rest are dying.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Copy)]
+pub struct Generations {
+    pub life: Rulestring,
+    pub states: u8,
+}
+
+pub const CONWAY: Rule = rulestring!("23/3");
+/// Conway's Life where live cells sometimes die anyway.
+pub const NOISY_CONWAY: Rule = Rule::Stochastic(Stochastic {
+    survive: [0., 0., 0.97, 0.97, 0., 0., 0., 0., 0.],
+    spawn: [0., 0., 0., 1., 0., 0., 0., 0., 0.],
+    neighbourhood: Neighbourhood::Moore,
+});
+pub const MAZE: Rule = rulestring!("12345/3");
+pub const MAZE_MICE: Rule = rulestring!("12345/37");
+pub const HEX_LIFE: Rule = rulestring!("34/2H");
+pub const BRIANS_BRAIN: Rule = generations!("/2", 3);
+pub const STAR_WARS: Rule = generations!("345/2", 4);
+pub const FROGS: Rule = generations!("12/34", 3);
+pub const BOSCO: Rule = Rule::LargerThanLife(LargerThanLife {
+    range: 5,
+    states: 0,
+    middle: true,
+    survive: (34, 58),
+    birth: (34, 45),
+    shape: LtlShape::Moore,
+});
+pub const MAJORITY: Rule = Rule::LargerThanLife(LargerThanLife {
+    range: 4,
+    states: 0,
+    middle: true,
+    survive: (41, 81),
+    birth: (41, 81),
+    shape: LtlShape::Moore,
+});
+pub const GLOBE: Rule = Rule::LargerThanLife(LargerThanLife {
+    range: 8,
+    states: 0,
+    middle: false,
+    survive: (163, 223),
+    birth: (74, 252),
+    shape: LtlShape::Moore,
+});
+pub const CRITTERS: Rule = Rule::Margolus(Margolus {
+    table: [15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0],
+});
+pub const BILLIARD_BALLS: Rule = Rule::Margolus(Margolus {
+    table: [0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15],
+});
+pub const TRON: Rule = Rule::Margolus(Margolus {
+    table: [15, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0],
+});
+/// Grains fall into empty cells below them and slide down diagonally.
+pub const SAND: Rule = Rule::Margolus(Margolus {
+    table: [0, 4, 8, 12, 4, 12, 12, 13, 8, 12, 12, 14, 12, 13, 14, 15],
+});
+pub const IMMIGRATION: Rule = species!("23/3", 2);
+pub const QUADLIFE: Rule = species!("23/3", 4);
+pub const LENIA: Rule = Rule::Lenia(Lenia {
+    radius: 13,
+    mu: 0.15,
+    sigma: 0.015,
+    dt: 0.1,
+    kernel: KernelShape::Lenia,
+});
+pub const SMOOTH_LIFE: Rule = Rule::Lenia(Lenia {
+    radius: 10,
+    mu: 0.28,
+    sigma: 0.05,
+    dt: 0.2,
+    kernel: KernelShape::SmoothLife,
+});
+pub const CORAL: Rule = Rule::GrayScott(GrayScott {
+    feed: 0.0545,
+    kill: 0.062,
+    diffuse_a: 1.,
+    diffuse_b: 0.5,
+});
+pub const MITOSIS: Rule = Rule::GrayScott(GrayScott {
+    feed: 0.0367,
+    kill: 0.0649,
+    diffuse_a: 1.,
+    diffuse_b: 0.5,
+});
+pub const SPOTS: Rule = Rule::GrayScott(GrayScott {
+    feed: 0.03,
+    kill: 0.062,
+    diffuse_a: 1.,
+    diffuse_b: 0.5,
+});
+pub const WIREWORLD: Rule = Rule::Wireworld;
+pub const FALLING_SAND: Rule = Rule::FallingSand;
+pub const LANGTONS_ANT: Rule = Rule::Turmite(Turmite::ant(&[Turn::Right, Turn::Left]));
+pub const SYMMETRIC_ANT: Rule = Rule::Turmite(Turmite::ant(&[
+    Turn::Left,
+    Turn::Left,
+    Turn::Right,
+    Turn::Right,
+]));
+pub const FALLING_STARS: Rule = Rule::Custom(0);
+pub const MAZE_CYCLE: Rule = Rule::Custom(1);
+pub const NOISE: Rule = Rule::Custom(2);
+pub const WORLEY_LINES: Rule = Rule::Custom(3);
+pub const SPACE: Rule = Rule::Custom(4);
+pub const PERLIN_NOISE: Rule = Rule::Custom(5);
+
+pub const RULES: &[(&str, Rule)] = &[
+    ("Conway", CONWAY),
+    ("Noisy Conway", NOISY_CONWAY),
+    ("Maze", MAZE),
+    ("Maze with mice", MAZE_MICE),
+    ("Hexagonal life", HEX_LIFE),
+    ("Immigration", IMMIGRATION),
+    ("QuadLife", QUADLIFE),
+    ("Brian's Brain", BRIANS_BRAIN),
+    ("Star Wars", STAR_WARS),
+    ("Frogs", FROGS),
+    ("Bosco's rule", BOSCO),
+    ("Majority", MAJORITY),
+    ("Globe", GLOBE),
+    ("Critters", CRITTERS),
+    ("Billiard ball machine", BILLIARD_BALLS),
+    ("Tron", TRON),
+    ("Sand", SAND),
+    ("Lenia", LENIA),
+    ("SmoothLife", SMOOTH_LIFE),
+    ("Coral", CORAL),
+    ("Mitosis", MITOSIS),
+    ("Spots", SPOTS),
+    ("Wireworld", WIREWORLD),
+    ("Falling sand", FALLING_SAND),
+    ("Langton's ant", LANGTONS_ANT),
+    ("Symmetric ant", SYMMETRIC_ANT),
+    ("Falling stars", FALLING_STARS),
+    ("Maze cycle", MAZE_CYCLE),
+    ("Noise", NOISE),
+    ("Worley noise", WORLEY_LINES),
+    ("Space", SPACE),
+    ("Perlin noise", PERLIN_NOISE),
+];
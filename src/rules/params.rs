@@ -0,0 +1,166 @@
+//! Typed knobs for automata. Each automaton declares its parameters with a
+//! range and default; the values picked in the settings are kept in the
+//! config under the automaton's name.
+
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use serde::{Deserialize, Serialize};
+
+/// A parameter an automaton can be tuned with.
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub name: &'static str,
+    pub kind: ParamKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum ParamKind {
+    Float {
+        default: f64,
+        range: RangeInclusive<f64>,
+    },
+    Int {
+        default: i64,
+        range: RangeInclusive<i64>,
+    },
+    Bool {
+        default: bool,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ParamValue {
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+impl Param {
+    pub fn float(name: &'static str, default: f64, range: RangeInclusive<f64>) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Float { default, range },
+        }
+    }
+
+    pub fn int(name: &'static str, default: i64, range: RangeInclusive<i64>) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Int { default, range },
+        }
+    }
+
+    pub fn bool(name: &'static str, default: bool) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Bool { default },
+        }
+    }
+
+    pub fn default_value(&self) -> ParamValue {
+        match self.kind {
+            ParamKind::Float { default, .. } => ParamValue::Float(default),
+            ParamKind::Int { default, .. } => ParamValue::Int(default),
+            ParamKind::Bool { default } => ParamValue::Bool(default),
+        }
+    }
+
+    /// `value` clamped into range, or `None` if it has the wrong type, like
+    /// after an automaton changed its parameters.
+    pub fn fit(&self, value: ParamValue) -> Option<ParamValue> {
+        match (&self.kind, value) {
+            (ParamKind::Float { range, .. }, ParamValue::Float(v)) => {
+                Some(ParamValue::Float(v.clamp(*range.start(), *range.end())))
+            }
+            (ParamKind::Int { range, .. }, ParamValue::Int(v)) => {
+                Some(ParamValue::Int(v.clamp(*range.start(), *range.end())))
+            }
+            (ParamKind::Bool { .. }, ParamValue::Bool(v)) => Some(ParamValue::Bool(v)),
+            _ => None,
+        }
+    }
+}
+
+/// Values of an automaton's parameters for one step.
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    values: Vec<(&'static str, ParamValue)>,
+}
+
+impl Params {
+    /// The declared parameters, taking saved values over the defaults where
+    /// they still fit.
+    pub fn new(params: &[Param], saved: Option<&BTreeMap<String, ParamValue>>) -> Self {
+        let values = params
+            .iter()
+            .map(|param| {
+                let value = saved
+                    .and_then(|saved| saved.get(param.name))
+                    .and_then(|&value| param.fit(value))
+                    .unwrap_or_else(|| param.default_value());
+
+                (param.name, value)
+            })
+            .collect();
+
+        Self { values }
+    }
+
+    fn get(&self, name: &str) -> ParamValue {
+        match self.values.iter().find(|(n, _)| *n == name) {
+            Some(&(_, value)) => value,
+            None => panic!("No parameter called {name}"),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// If there is no float parameter called `name`, as for the other
+    /// getters.
+    pub fn float(&self, name: &str) -> f64 {
+        match self.get(name) {
+            ParamValue::Float(v) => v,
+            _ => panic!("{name} isn't a float parameter"),
+        }
+    }
+
+    pub fn int(&self, name: &str) -> i64 {
+        match self.get(name) {
+            ParamValue::Int(v) => v,
+            _ => panic!("{name} isn't an integer parameter"),
+        }
+    }
+
+    pub fn bool(&self, name: &str) -> bool {
+        match self.get(name) {
+            ParamValue::Bool(v) => v,
+            _ => panic!("{name} isn't a boolean parameter"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_values_are_checked_against_the_declaration() {
+        let params = [
+            Param::int("Odds", 40, 1..=100),
+            Param::float("Scale", 0.5, 0.0..=1.0),
+            Param::bool("Wrap", true),
+        ];
+        let saved = BTreeMap::from([
+            ("Odds".to_string(), ParamValue::Int(500)),
+            ("Scale".to_string(), ParamValue::Bool(false)),
+            ("Gone".to_string(), ParamValue::Int(1)),
+        ]);
+
+        let values = Params::new(&params, Some(&saved));
+
+        assert_eq!(values.int("Odds"), 100);
+        assert_eq!(values.float("Scale"), 0.5);
+        assert!(values.bool("Wrap"));
+        assert_eq!(Params::new(&params, None).int("Odds"), 40);
+    }
+}
@@ -0,0 +1,377 @@
+//! Parsing and printing rules at runtime.
+//!
+//! Life-like rules are read as `B3/S23`, `S23/B3` or the older survival-first
+//! `23/3`, in any case, with an optional `H` or `V` neighbourhood suffix.
+//! Generations rules add the number of states, as in `B2/S/C3` or `/2/3`.
+//! Larger than Life and isotropic rules use their own notations.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+
+use super::{automaton, Generations, LargerThanLife, Neighbourhood, Rule, Rulestring, RULES};
+
+impl Neighbourhood {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Neighbourhood::Moore => "",
+            Neighbourhood::Hexagonal => "H",
+            Neighbourhood::VonNeumann => "V",
+        }
+    }
+
+    fn max(self) -> usize {
+        self.mask().count_ones() as usize
+    }
+}
+
+/// Reads the neighbour counts of one half of a rulestring.
+fn parse_counts(text: &str, neighbourhood: Neighbourhood) -> Result<[bool; 9]> {
+    let mut counts = [false; 9];
+
+    for c in text.chars() {
+        let Some(n) = c.to_digit(10).map(|n| n as usize) else {
+            bail!("Expected a neighbour count, got '{c}'");
+        };
+
+        if n > neighbourhood.max() {
+            bail!(
+                "A cell can't have {n} neighbours in the {} neighbourhood",
+                neighbourhood.to_string().to_lowercase()
+            );
+        }
+
+        counts[n] = true;
+    }
+
+    Ok(counts)
+}
+
+fn fmt_counts(f: &mut fmt::Formatter<'_>, counts: &[bool; 9]) -> fmt::Result {
+    for (n, _) in counts.iter().enumerate().filter(|(_, &on)| on) {
+        write!(f, "{n}")?;
+    }
+
+    Ok(())
+}
+
+impl FromStr for Rulestring {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+
+        let (s, neighbourhood) = match s.strip_suffix('h') {
+            Some(s) => (s, Neighbourhood::Hexagonal),
+            None => match s.strip_suffix('v') {
+                Some(s) => (s, Neighbourhood::VonNeumann),
+                None => (s.as_str(), Neighbourhood::Moore),
+            },
+        };
+
+        let Some((first, second)) = s.split_once('/') else {
+            bail!("Expected a rule like B3/S23, S23/B3 or 23/3");
+        };
+
+        let (birth, survive) = match (first.strip_prefix('b'), second.strip_prefix('s')) {
+            (Some(birth), Some(survive)) => (birth, survive),
+            (None, None) => match (first.strip_prefix('s'), second.strip_prefix('b')) {
+                (Some(survive), Some(birth)) => (birth, survive),
+                (None, None) => (second, first),
+                _ => bail!("Expected both halves of the rule to start with B or S"),
+            },
+            _ => bail!("Expected both halves of the rule to start with B or S"),
+        };
+
+        Ok(Self {
+            survive: parse_counts(survive, neighbourhood)?,
+            spawn: parse_counts(birth, neighbourhood)?,
+            neighbourhood,
+        })
+    }
+}
+
+impl fmt::Display for Rulestring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        fmt_counts(f, &self.spawn)?;
+        write!(f, "/S")?;
+        fmt_counts(f, &self.survive)?;
+        write!(f, "{}", self.neighbourhood.suffix())
+    }
+}
+
+impl FromStr for Generations {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        // The neighbourhood suffix goes after the states, as in `B2/S34/C3H`.
+        let (s, suffix) = match s.char_indices().last() {
+            Some((i, 'h' | 'H' | 'v' | 'V')) => s.split_at(i),
+            _ => (s, ""),
+        };
+        let Some((life, states)) = s.rsplit_once('/') else {
+            bail!("Expected a rule like B2/S/C3 or /2/3");
+        };
+
+        let digits = states.trim_start_matches(['c', 'C', 'g', 'G']);
+        let Ok(states) = digits.parse::<u8>() else {
+            bail!("Expected a number of states, got '{states}'");
+        };
+        if states < 2 {
+            bail!("Generations rules need at least 2 states");
+        }
+
+        Ok(Self {
+            life: format!("{life}{suffix}").parse()?,
+            states,
+        })
+    }
+}
+
+impl fmt::Display for Generations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let life = self.life.to_string();
+        let (counts, suffix) = life.split_at(life.len() - self.life.neighbourhood.suffix().len());
+
+        write!(f, "{counts}/C{}{suffix}", self.states)
+    }
+}
+
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    /// Reads any rule that has a notation, working out which kind it is from
+    /// its shape.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+
+        match lower.as_str() {
+            "wireworld" => return Ok(Rule::Wireworld),
+            "falling sand" => return Ok(Rule::FallingSand),
+            _ => {}
+        }
+
+        if lower.starts_with("lenia") || lower.starts_with("smoothlife") {
+            return Ok(Rule::Lenia(s.parse()?));
+        }
+
+        if lower.starts_with("immigration") || lower.starts_with("quadlife") {
+            return Ok(Rule::Species(s.parse()?));
+        }
+
+        if lower.starts_with("gray-scott") {
+            return Ok(Rule::GrayScott(s.parse()?));
+        }
+
+        if lower.starts_with("ms,") {
+            return Ok(Rule::Margolus(s.parse()?));
+        }
+
+        if lower.starts_with("ant") || lower.starts_with('{') {
+            return Ok(Rule::Turmite(s.parse()?));
+        }
+
+        if lower.starts_with('r') {
+            return Ok(Rule::LargerThanLife(s.parse::<LargerThanLife>()?));
+        }
+
+        if lower.contains(':') {
+            return Ok(Rule::Stochastic(s.parse()?));
+        }
+
+        if lower.matches('/').count() == 2 {
+            return Ok(Rule::Generations(s.parse()?));
+        }
+
+        let hensel = lower
+            .chars()
+            .any(|c| c.is_ascii_alphabetic() && !"bshv".contains(c))
+            || lower.contains('-');
+
+        Ok(if hensel {
+            Rule::Isotropic(s.parse()?)
+        } else {
+            Rule::Rulestring(s.parse()?)
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Rulestring(rule) => rule.fmt(f),
+            Rule::Stochastic(rule) => rule.fmt(f),
+            Rule::Margolus(rule) => rule.fmt(f),
+            Rule::Generations(rule) => rule.fmt(f),
+            Rule::LargerThanLife(rule) => rule.fmt(f),
+            Rule::Isotropic(rule) => rule.fmt(f),
+            Rule::Species(rule) => rule.fmt(f),
+            Rule::Lenia(rule) => rule.fmt(f),
+            Rule::GrayScott(rule) => rule.fmt(f),
+            Rule::Wireworld => write!(f, "Wireworld"),
+            Rule::FallingSand => write!(f, "Falling sand"),
+            Rule::Turmite(rule) => rule.fmt(f),
+            Rule::Custom(i) => match automaton(*i) {
+                Some(automaton) => write!(f, "{}", automaton.name()),
+                None => write!(f, "Custom rule {i}"),
+            },
+        }
+    }
+}
+
+impl Rule {
+    /// The name of the rule in [`RULES`], or its notation if it isn't there.
+    pub fn name(&self) -> String {
+        match RULES.iter().find(|(_, rule)| rule == self) {
+            Some((name, _)) => name.to_string(),
+            None => self.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{
+        BILLIARD_BALLS, BRIANS_BRAIN, CONWAY, CORAL, CRITTERS, FALLING_SAND, HEX_LIFE, IMMIGRATION,
+        LANGTONS_ANT, LENIA, MAZE, NOISY_CONWAY, QUADLIFE, SAND, SMOOTH_LIFE, SYMMETRIC_ANT,
+        WIREWORLD,
+    };
+
+    #[test]
+    fn parses_rulestrings() {
+        for text in ["B3/S23", "S23/B3", "23/3", "b3/s23", " s23/b3 "] {
+            assert!(text.parse::<Rule>().unwrap() == CONWAY, "{text}");
+        }
+
+        assert!("B3/S12345".parse::<Rule>().unwrap() == MAZE);
+        assert!("B2/S34H".parse::<Rule>().unwrap() == HEX_LIFE);
+        for rule in [CONWAY, MAZE, HEX_LIFE] {
+            assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+        }
+        assert_eq!(CONWAY.to_string(), "B3/S23");
+        assert_eq!(HEX_LIFE.name(), "Hexagonal life");
+    }
+
+    #[test]
+    fn parses_generations() {
+        assert!("/2/3".parse::<Rule>().unwrap() == BRIANS_BRAIN);
+        assert!("B2/S/C3".parse::<Rule>().unwrap() == BRIANS_BRAIN);
+        assert_eq!(
+            "345/2/4H".parse::<Rule>().unwrap().to_string(),
+            "B2/S345/C4H"
+        );
+        assert!(BRIANS_BRAIN.to_string().parse::<Rule>().unwrap() == BRIANS_BRAIN);
+        assert_eq!(BRIANS_BRAIN.to_string(), "B2/S/C3");
+    }
+
+    #[test]
+    fn parses_isotropic_rules() {
+        let rule = "B3/S23-a4i".parse::<Rule>().unwrap();
+        assert!(rule.is_isotropic());
+        assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+    }
+
+    #[test]
+    fn parses_larger_than_life() {
+        let rule = "R5,C0,M1,S34..58,B34..45,NM".parse::<Rule>().unwrap();
+        assert!(rule.is_larger_than_life());
+        assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+    }
+
+    #[test]
+    fn parses_wireworld() {
+        assert!("wireworld".parse::<Rule>().unwrap() == WIREWORLD);
+        assert!(WIREWORLD.to_string().parse::<Rule>().unwrap() == WIREWORLD);
+    }
+
+    #[test]
+    fn parses_turmites() {
+        assert!("Ant RL".parse::<Rule>().unwrap() == LANGTONS_ANT);
+        assert!("{{{1, 2, 0}, {0, 8, 0}}}".parse::<Rule>().unwrap() == LANGTONS_ANT);
+        let turmite = "{{{1,2,1},{1,8,1}},{{1,2,1},{0,2,0}}}";
+        assert_eq!(turmite.parse::<Rule>().unwrap().to_string(), turmite);
+
+        for rule in [LANGTONS_ANT, SYMMETRIC_ANT] {
+            assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+        }
+    }
+
+    #[test]
+    fn parses_margolus_rules() {
+        assert!(
+            "MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15"
+                .parse::<Rule>()
+                .unwrap()
+                == BILLIARD_BALLS
+        );
+
+        for rule in [BILLIARD_BALLS, CRITTERS, SAND] {
+            assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+        }
+    }
+
+    #[test]
+    fn parses_falling_sand() {
+        assert!(FALLING_SAND.to_string().parse::<Rule>().unwrap() == FALLING_SAND);
+    }
+
+    #[test]
+    fn parses_continuous_rules() {
+        for rule in [LENIA, SMOOTH_LIFE] {
+            assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+        }
+    }
+
+    #[test]
+    fn parses_reaction_diffusion() {
+        assert!(CORAL.to_string().parse::<Rule>().unwrap() == CORAL);
+    }
+
+    #[test]
+    fn parses_species_rules() {
+        for rule in [IMMIGRATION, QUADLIFE] {
+            assert!(rule.to_string().parse::<Rule>().unwrap() == rule, "{rule}");
+        }
+    }
+
+    #[test]
+    fn parses_stochastic_rules() {
+        assert_eq!(
+            "b3:1/s3:0.5,2:0.25h".parse::<Rule>().unwrap().to_string(),
+            "B3:1/S2:0.25,3:0.5H"
+        );
+        assert!(NOISY_CONWAY.to_string().parse::<Rule>().unwrap() == NOISY_CONWAY);
+    }
+
+    #[test]
+    fn rejects_bad_rules() {
+        for text in [
+            "",
+            "B3S23",
+            "B3/B23",
+            "B3/23",
+            "B9/S23",
+            "B3/S2.3",
+            "B7/S2H",
+            "B3/S5V",
+            "/2/1",
+            "/2/x",
+            "Ant R",
+            "Ant RX",
+            "{{{1,2,0}}}",
+            "{{{2,2,0},{0,8,0}}}",
+            "{{{1,3,0},{0,8,0}}}",
+            "{{{1,2,1},{0,8,0}}}",
+            "MS,D0;1;2",
+            "B3:2/S23",
+            "B3:1/S9:0.5",
+            "MS,D0;1;2;3;4;5;6;7;8;9;10;11;12;13;14;16",
+        ] {
+            assert!(text.parse::<Rule>().is_err(), "{text}");
+        }
+    }
+}
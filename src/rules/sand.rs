@@ -0,0 +1,78 @@
+//! Materials of the falling sand mode. Every non-empty cell is alive with
+//! its material as the cell state; plain live cells count as sand. Fire and
+//! smoke keep their age in the cell's heat.
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::{board::Tile, utils::GColor};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize, Display)]
+pub enum Material {
+    Empty,
+    #[default]
+    Sand,
+    Water,
+    Stone,
+    Fire,
+    Smoke,
+}
+
+pub const MATERIALS: &[Material] = &[
+    Material::Empty,
+    Material::Sand,
+    Material::Water,
+    Material::Stone,
+    Material::Fire,
+    Material::Smoke,
+];
+
+impl Material {
+    pub fn of(tile: Tile) -> Self {
+        if !tile.alive {
+            return Material::Empty;
+        }
+
+        match tile.state {
+            2 => Material::Water,
+            3 => Material::Stone,
+            4 => Material::Fire,
+            5 => Material::Smoke,
+            _ => Material::Sand,
+        }
+    }
+
+    /// A tile of this material, with no heat.
+    pub fn tile(self) -> Tile {
+        Tile {
+            alive: self != Material::Empty,
+            heat: 0,
+            state: self as u8,
+        }
+    }
+
+    /// How heavy the material is. Falling materials sink through lighter
+    /// ones, rising ones only move into empty cells and stone never moves.
+    pub fn density(self) -> u8 {
+        match self {
+            Material::Empty => 0,
+            Material::Fire | Material::Smoke => 1,
+            Material::Water => 2,
+            Material::Sand => 3,
+            Material::Stone => u8::MAX,
+        }
+    }
+
+    /// Color of the material, with fire fading to smoke as it ages.
+    pub fn color(self, age: u8) -> Option<GColor> {
+        Some(match self {
+            Material::Empty => return None,
+            Material::Sand => GColor::from_hex(0xE2C572),
+            Material::Water => GColor::from_hex(0x2F6FD6),
+            Material::Stone => GColor::from_hex(0x7A7A7A),
+            Material::Fire => GColor::from_hex(0xFF7A1A)
+                .blend(&GColor::from_hex(0xB02010), (age as f32 / 40.).min(1.)),
+            Material::Smoke => GColor::from_hex(0x4A4A4A),
+        })
+    }
+}
@@ -0,0 +1,91 @@
+//! Life-like rules where live cells belong to one of several species, like
+//! Immigration with two and QuadLife with four. Cells survive as the species
+//! they are, and newborns take the most common species among their live
+//! neighbours. Under QuadLife, three parents of different species make a
+//! cell of the fourth.
+//!
+//! A live cell's species is its state minus one, so plain live cells count
+//! as the first species. Written like `Immigration B3/S23` or
+//! `QuadLife B3/S23`.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Rulestring;
+use crate::board::Tile;
+
+/// Most species a rule can have.
+pub const MAX_SPECIES: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    pub life: Rulestring,
+    /// 2 for Immigration, 4 for QuadLife.
+    pub species: u8,
+}
+
+impl Species {
+    /// Species of a cell born next to `counts` live cells of each species.
+    pub fn newborn(&self, counts: &[u8; MAX_SPECIES]) -> u8 {
+        let counts = &counts[..self.species as usize];
+        let most = *counts.iter().max().unwrap_or(&0);
+
+        if most == 1 && counts.iter().filter(|&&n| n == 1).count() == 3 {
+            if let Some(missing) = counts.iter().position(|&n| n == 0) {
+                return missing as u8;
+            }
+        }
+
+        counts.iter().position(|&n| n == most).unwrap_or(0) as u8
+    }
+}
+
+/// Species of a tile, `None` for dead ones.
+pub fn species_of(tile: Tile) -> Option<u8> {
+    tile.alive.then(|| tile.state.saturating_sub(1))
+}
+
+/// A live tile of the given species.
+pub fn species_tile(species: u8) -> Tile {
+    Tile {
+        alive: true,
+        heat: 0,
+        state: species + 1,
+    }
+}
+
+impl FromStr for Species {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let Some((name, life)) = s.split_once(char::is_whitespace) else {
+            bail!("Expected a rule like Immigration B3/S23 or QuadLife B3/S23");
+        };
+
+        let species = match name.to_ascii_lowercase().as_str() {
+            "immigration" => 2,
+            "quadlife" => 4,
+            _ => bail!("Expected Immigration or QuadLife, not '{name}'"),
+        };
+
+        Ok(Self {
+            life: life.parse()?,
+            species,
+        })
+    }
+}
+
+impl fmt::Display for Species {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = if self.species == 2 {
+            "Immigration"
+        } else {
+            "QuadLife"
+        };
+
+        write!(f, "{name} {}", self.life)
+    }
+}
@@ -0,0 +1,116 @@
+//! Life-like rules where every birth and survival count has a chance of
+//! happening instead of always or never, drawn from the board's random
+//! number generator so seeded runs repeat.
+//!
+//! Written like a rulestring with comma separated counts that each carry
+//! their chance, as in `B3:1/S2:0.95,3:0.95`, with the usual `H` or `V`
+//! neighbourhood suffix. Counts left out never happen.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{Neighbourhood, Rulestring};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stochastic {
+    /// Chance of a live cell surviving with each number of neighbours.
+    pub survive: [f32; 9],
+    /// Chance of a dead cell being born with each number of neighbours.
+    pub spawn: [f32; 9],
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
+}
+
+/// The same rule with every count certain.
+impl From<Rulestring> for Stochastic {
+    fn from(rule: Rulestring) -> Self {
+        Self {
+            survive: rule.survive.map(|on| on as u8 as f32),
+            spawn: rule.spawn.map(|on| on as u8 as f32),
+            neighbourhood: rule.neighbourhood,
+        }
+    }
+}
+
+fn parse_chances(text: &str, neighbourhood: Neighbourhood) -> Result<[f32; 9]> {
+    let mut chances = [0.; 9];
+    let max = neighbourhood.mask().count_ones() as usize;
+
+    for entry in text.split(',').filter(|e| !e.is_empty()) {
+        let Some((n, chance)) = entry.split_once(':') else {
+            bail!("Expected a count and its chance like 3:0.5, got '{entry}'");
+        };
+        let n = n
+            .parse::<usize>()
+            .with_context(|| format!("Expected a neighbour count, got '{n}'"))?;
+        let chance = chance
+            .parse::<f32>()
+            .with_context(|| format!("Expected a chance, got '{chance}'"))?;
+
+        if n > max {
+            bail!(
+                "A cell can't have {n} neighbours in the {} neighbourhood",
+                neighbourhood.to_string().to_lowercase()
+            );
+        }
+        if !(0. ..=1.).contains(&chance) {
+            bail!("Chances go from 0 to 1, got {chance}");
+        }
+
+        chances[n] = chance;
+    }
+
+    Ok(chances)
+}
+
+fn fmt_chances(f: &mut fmt::Formatter<'_>, chances: &[f32; 9]) -> fmt::Result {
+    let entries: Vec<String> = chances
+        .iter()
+        .enumerate()
+        .filter(|(_, &chance)| chance > 0.)
+        .map(|(n, chance)| format!("{n}:{chance}"))
+        .collect();
+
+    write!(f, "{}", entries.join(","))
+}
+
+impl FromStr for Stochastic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+
+        let (s, neighbourhood) = match s.strip_suffix('h') {
+            Some(s) => (s, Neighbourhood::Hexagonal),
+            None => match s.strip_suffix('v') {
+                Some(s) => (s, Neighbourhood::VonNeumann),
+                None => (s.as_str(), Neighbourhood::Moore),
+            },
+        };
+
+        let Some((birth, survive)) = s
+            .split_once('/')
+            .and_then(|(b, s)| Some((b.strip_prefix('b')?, s.strip_prefix('s')?)))
+        else {
+            bail!("Expected a rule like B3:1/S2:0.95,3:0.95");
+        };
+
+        Ok(Self {
+            survive: parse_chances(survive, neighbourhood)?,
+            spawn: parse_chances(birth, neighbourhood)?,
+            neighbourhood,
+        })
+    }
+}
+
+impl fmt::Display for Stochastic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        fmt_chances(f, &self.spawn)?;
+        write!(f, "/S")?;
+        fmt_chances(f, &self.survive)?;
+        write!(f, "{}", self.neighbourhood.suffix())
+    }
+}
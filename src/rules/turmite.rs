@@ -0,0 +1,263 @@
+//! Turmites: agents that walk over the board, reading the color of their
+//! cell, writing a new one, turning and stepping forward. Langton's ant is
+//! the single state turmite that turns right on color 0 and left on color 1.
+//!
+//! Tables use Golly's notation, `{{{1,2,0},{0,8,0}}}` being Langton's ant:
+//! for each state, for each color, the color to write, the turn (1 none,
+//! 2 right, 4 u-turn, 8 left) and the next state. Ants with one state can be
+//! written as a turn per color instead, like `Ant RL` or `Ant LLRR`.
+//!
+//! Color 0 is a dead cell and color 1 a live one. Higher colors are live
+//! cells with the color as their state, which unbounded boards don't keep.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+pub const MAX_STATES: usize = 4;
+pub const MAX_COLORS: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+impl Turn {
+    fn code(self) -> u8 {
+        match self {
+            Turn::None => 1,
+            Turn::Right => 2,
+            Turn::UTurn => 4,
+            Turn::Left => 8,
+        }
+    }
+
+    fn letter(self) -> char {
+        match self {
+            Turn::None => 'N',
+            Turn::Right => 'R',
+            Turn::UTurn => 'U',
+            Turn::Left => 'L',
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize, Display)]
+pub enum Heading {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Heading {
+    pub fn turn(self, turn: Turn) -> Self {
+        let quarters = match turn {
+            Turn::None => 0,
+            Turn::Right => 1,
+            Turn::UTurn => 2,
+            Turn::Left => 3,
+        };
+
+        [Heading::North, Heading::East, Heading::South, Heading::West]
+            [(self as usize + quarters) % 4]
+    }
+
+    /// One cell forward, with y growing downwards.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Heading::North => (0, -1),
+            Heading::East => (1, 0),
+            Heading::South => (0, 1),
+            Heading::West => (-1, 0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct TurmiteMove {
+    pub write: u8,
+    pub turn: Turn,
+    pub next: u8,
+}
+
+const NO_MOVE: TurmiteMove = TurmiteMove {
+    write: 0,
+    turn: Turn::None,
+    next: 0,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Turmite {
+    pub states: u8,
+    pub colors: u8,
+    /// Moves by state and then color. Entries past `states` and `colors` are
+    /// unused.
+    pub table: [[TurmiteMove; MAX_COLORS]; MAX_STATES],
+}
+
+impl Turmite {
+    /// A single state ant turning `turns[c]` on color `c` and moving the
+    /// color on by one.
+    pub const fn ant(turns: &[Turn]) -> Self {
+        let mut table = [[NO_MOVE; MAX_COLORS]; MAX_STATES];
+        let mut c = 0;
+
+        while c < turns.len() {
+            table[0][c] = TurmiteMove {
+                write: ((c + 1) % turns.len()) as u8,
+                turn: turns[c],
+                next: 0,
+            };
+            c += 1;
+        }
+
+        Self {
+            states: 1,
+            colors: turns.len() as u8,
+            table,
+        }
+    }
+
+    /// The move of an agent in `state` on a cell of `color`. Out of range
+    /// values, like those left over from another rule, are clamped.
+    pub fn step(&self, state: u8, color: u8) -> TurmiteMove {
+        self.table[state.min(self.states - 1) as usize][color.min(self.colors - 1) as usize]
+    }
+
+    /// The turns of a single state ant, `None` for other turmites.
+    fn ant_turns(&self) -> Option<Vec<Turn>> {
+        if self.states != 1 {
+            return None;
+        }
+
+        (0..self.colors)
+            .map(|c| {
+                let m = self.table[0][c as usize];
+                (m.write == (c + 1) % self.colors && m.next == 0).then_some(m.turn)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Turmite {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s: String = s.split_whitespace().collect();
+
+        if s.get(..3).is_some_and(|p| p.eq_ignore_ascii_case("ant")) {
+            let letters = &s[3..];
+            if !(2..=MAX_COLORS).contains(&letters.len()) {
+                bail!("Ants need between 2 and {MAX_COLORS} turns, one per color");
+            }
+
+            let turns = letters
+                .chars()
+                .map(|c| match c.to_ascii_uppercase() {
+                    'N' => Ok(Turn::None),
+                    'R' => Ok(Turn::Right),
+                    'U' => Ok(Turn::UTurn),
+                    'L' => Ok(Turn::Left),
+                    _ => bail!("Expected a turn (L, R, N or U), got '{c}'"),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            return Ok(Self::ant(&turns));
+        }
+
+        let Some(body) = s.strip_prefix("{{{").and_then(|s| s.strip_suffix("}}}")) else {
+            bail!("Expected a turmite like {{{{{{1,2,0}},{{0,8,0}}}}}} or Ant RL");
+        };
+
+        let rows: Vec<Vec<&str>> = body
+            .split("}},{{")
+            .map(|state| state.split("},{").collect())
+            .collect();
+        let colors = rows[0].len();
+
+        if rows.len() > MAX_STATES {
+            bail!("Turmites can have at most {MAX_STATES} states");
+        }
+        if !(2..=MAX_COLORS).contains(&colors) {
+            bail!("Turmites need between 2 and {MAX_COLORS} colors");
+        }
+
+        let mut table = [[NO_MOVE; MAX_COLORS]; MAX_STATES];
+
+        for (state, row) in rows.iter().enumerate() {
+            if row.len() != colors {
+                bail!("Every state needs a move for each of the {colors} colors");
+            }
+
+            for (color, text) in row.iter().enumerate() {
+                let numbers = match text
+                    .split(',')
+                    .map(str::parse::<u8>)
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(numbers) => numbers,
+                    Err(_) => bail!("Expected numbers in '{{{text}}}'"),
+                };
+                let &[write, turn, next] = numbers.as_slice() else {
+                    bail!("Expected a color, turn and state in '{{{text}}}'");
+                };
+
+                if write as usize >= colors {
+                    bail!("There is no color {write}");
+                }
+                if next as usize >= rows.len() {
+                    bail!("There is no state {next}");
+                }
+
+                let turn = match turn {
+                    1 => Turn::None,
+                    2 => Turn::Right,
+                    4 => Turn::UTurn,
+                    8 => Turn::Left,
+                    _ => bail!("Expected a turn of 1, 2, 4 or 8, got {turn}"),
+                };
+
+                table[state][color] = TurmiteMove { write, turn, next };
+            }
+        }
+
+        Ok(Self {
+            states: rows.len() as u8,
+            colors: colors as u8,
+            table,
+        })
+    }
+}
+
+impl fmt::Display for Turmite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(turns) = self.ant_turns() {
+            return write!(
+                f,
+                "Ant {}",
+                turns.iter().map(|t| t.letter()).collect::<String>()
+            );
+        }
+
+        let states: Vec<String> = self.table[..self.states as usize]
+            .iter()
+            .map(|row| {
+                let moves: Vec<String> = row[..self.colors as usize]
+                    .iter()
+                    .map(|m| format!("{{{},{},{}}}", m.write, m.turn.code(), m.next))
+                    .collect();
+
+                format!("{{{}}}", moves.join(","))
+            })
+            .collect();
+
+        write!(f, "{{{}}}", states.join(","))
+    }
+}
@@ -0,0 +1,60 @@
+//! Wireworld: electrons running along wires, for building logic circuits.
+//!
+//! Heads become tails, tails become conductor again and a conductor turns
+//! into a head when one or two of its eight neighbours are heads. Heads are
+//! the live cells, tails and conductors are kept as cell states.
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::board::Tile;
+
+/// Cell state of an electron tail.
+pub const TAIL: u8 = 1;
+/// Cell state of a conductor.
+pub const CONDUCTOR: u8 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize, Display)]
+pub enum WireworldCell {
+    Empty,
+    #[default]
+    Conductor,
+    #[strum(to_string = "Electron head")]
+    Head,
+    #[strum(to_string = "Electron tail")]
+    Tail,
+}
+
+pub const WIREWORLD_CELLS: &[WireworldCell] = &[
+    WireworldCell::Empty,
+    WireworldCell::Conductor,
+    WireworldCell::Head,
+    WireworldCell::Tail,
+];
+
+impl WireworldCell {
+    pub fn of(tile: Tile) -> Self {
+        match (tile.alive, tile.state) {
+            (true, _) => WireworldCell::Head,
+            (false, TAIL) => WireworldCell::Tail,
+            (false, CONDUCTOR) => WireworldCell::Conductor,
+            _ => WireworldCell::Empty,
+        }
+    }
+
+    /// A tile in this state, with no heat.
+    pub fn tile(self) -> Tile {
+        let (alive, state) = match self {
+            WireworldCell::Empty => (false, 0),
+            WireworldCell::Conductor => (false, CONDUCTOR),
+            WireworldCell::Head => (true, 0),
+            WireworldCell::Tail => (false, TAIL),
+        };
+
+        Tile {
+            alive,
+            heat: 0,
+            state,
+        }
+    }
+}
@@ -1,16 +1,24 @@
 use std::{
+    collections::BTreeMap,
     sync::{Arc, Mutex},
     thread::Thread,
 };
 
 use crate::{
-    board::Board,
+    board::{Anchor, Board, ANCHORS, TOPOLOGIES},
     color_schemes::THEMES,
-    game::Game,
+    config::{Engine, HeatCoupling},
+    game::{hashlife_blocker, Game},
+    hashlife::MAX_JUMP,
     input::Tool,
-    notify_info,
+    notify_info, notify_warning,
+    preview::RulePreview,
     recording::Recording,
-    rules::{CONWAY, FALLING_STARS, MAZE, MAZE_MICE, RULES},
+    rules::{
+        automata, automaton, GrayScott, Lenia, Margolus, Param, ParamKind, ParamValue, Params,
+        Rule, Seeding, Stochastic, CONWAY, FALLING_STARS, KERNEL_SHAPES, MATERIALS, MAX_RADIUS,
+        MAZE, MAZE_MICE, RULES, SEEDINGS, WIREWORLD_CELLS,
+    },
     utils::GColor,
 };
 use egui_macroquad::{
@@ -26,6 +34,12 @@ pub struct UiState {
     recording_length: usize,
     recording_upscale: usize,
     recording_frame_rate: usize,
+    jump_generations: u64,
+    resize_anchor: Anchor,
+    crop_to_live_cells: bool,
+    custom_rule: String,
+    saved_rule_name: String,
+    rule_preview: RulePreview,
 }
 
 impl Game {
@@ -42,6 +56,23 @@ impl Game {
                         if self.config.enable_heat {
                             color_picker(ui, &mut self.config.hot_color, "Hot color");
                         }
+                        if self.config.rule.decay_states().is_some() {
+                            color_picker(ui, &mut self.config.dying_color, "Dying color");
+                        }
+                        if self.config.rule.is_wireworld() {
+                            color_picker(ui, &mut self.config.wire_color, "Conductor color");
+                            color_picker(ui, &mut self.config.head_color, "Electron head color");
+                            color_picker(ui, &mut self.config.tail_color, "Electron tail color");
+                        }
+                        if let Rule::Species(rule) = self.config.rule {
+                            for (i, color) in self.config.species_colors
+                                [..rule.species as usize]
+                                .iter_mut()
+                                .enumerate()
+                            {
+                                color_picker(ui, color, &format!("Species {} color", i + 1));
+                            }
+                        }
                         color_picker(ui, &mut self.config.dead_color, "Dead color");
                         color_picker(ui, &mut self.config.highlight_color, "Highlight color");
 
@@ -85,6 +116,17 @@ impl Game {
                                     .text("Heat intensity (default 0.5)"),
                             );
                         }
+
+                        ui.add_space(8.);
+
+                        ui.checkbox(
+                            &mut self.config.couple_heat,
+                            "Heat feeds back into life-like rules",
+                        );
+
+                        if self.config.couple_heat {
+                            heat_coupling_editor(ui, &mut self.config.heat_coupling);
+                        }
                     });
 
                     ui.collapsing("Board", |ui| {
@@ -97,12 +139,86 @@ impl Game {
                             "Height (default 108)",
                         );
 
+                        {
+                            let _ = ComboBox::from_label("Topology")
+                                .selected_text(self.config.topology.to_string())
+                                .show_ui(ui, |ui| {
+                                    for topology in TOPOLOGIES {
+                                        ui.selectable_value(
+                                            &mut self.config.topology,
+                                            *topology,
+                                            topology.to_string(),
+                                        );
+                                    }
+                                });
+                        }
+
+                        if self.board.topology() != self.config.topology
+                            && ui.button("Apply topology to board").clicked()
+                        {
+                            self.board.set_topology(self.config.topology);
+                            self.warn_if_rule_cant_step();
+                        }
+
                         ui.add_space(4.);
 
                         if ui.button("Create new board").clicked() {
-                            self.board = Board::new(self.config.width, self.config.height);
+                            self.board = Board::with_topology(
+                                self.config.width,
+                                self.config.height,
+                                self.config.topology,
+                            );
+                            self.board.reseed(self.config.seed);
+                            self.warn_if_rule_cant_step();
                         }
 
+                        ui.add_space(4.);
+
+                        ui.checkbox(
+                            &mut self.ui_state.crop_to_live_cells,
+                            "Crop to live cells instead",
+                        );
+
+                        if !self.ui_state.crop_to_live_cells {
+                            let _ = ComboBox::from_label("Anchor")
+                                .selected_text(self.ui_state.resize_anchor.to_string())
+                                .show_ui(ui, |ui| {
+                                    for anchor in ANCHORS {
+                                        ui.selectable_value(
+                                            &mut self.ui_state.resize_anchor,
+                                            *anchor,
+                                            anchor.to_string(),
+                                        );
+                                    }
+                                });
+                        }
+
+                        if ui.button("Resize board").clicked() {
+                            if !self.ui_state.crop_to_live_cells {
+                                self.board.resize(
+                                    self.config.width,
+                                    self.config.height,
+                                    self.ui_state.resize_anchor,
+                                );
+                            } else if let Some(bounds) = self.board.live_bounds() {
+                                self.board.crop(bounds);
+                                self.config.width = self.board.width();
+                                self.config.height = self.board.height();
+                            } else {
+                                notify_warning!(self, "There are no live cells to crop to.");
+                            }
+                        }
+
+                        ui.add_space(4.);
+
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut self.config.seed));
+                            if ui.button("Reseed").clicked() {
+                                self.board.reseed(self.config.seed);
+                            }
+                        });
+                        ui.label("Same seed and starting board, same run.");
+
                         ui.add_space(16.);
 
                         if ui.button("Clear board (C)").clicked() {
@@ -173,15 +289,227 @@ impl Game {
                             "Simulation speed (default 1)",
                         );
 
+                        usize_slider(ui, &mut self.config.threads, 1, 32, "Worker threads");
+
                         ui.add_space(8.);
 
+                        let rule_before = self.config.rule;
                         let _ = ComboBox::from_label("Rule")
-                            .selected_text("Select rule")
+                            .selected_text(self.config.rule_name())
                             .show_ui(ui, |ui| {
                                 for (name, rule) in RULES {
                                     ui.selectable_value(&mut self.config.rule, *rule, *name);
                                 }
+
+                                // Automata registered by other crates.
+                                for (name, rule) in automata() {
+                                    if !RULES.iter().any(|(_, r)| *r == rule) {
+                                        ui.selectable_value(&mut self.config.rule, rule, name);
+                                    }
+                                }
+
+                                if !self.config.saved_rules.is_empty() {
+                                    ui.separator();
+                                }
+                                for (name, rule) in &self.config.saved_rules {
+                                    ui.selectable_value(&mut self.config.rule, *rule, name);
+                                }
                             });
+
+                        ui.label("Custom rule:");
+                        let response = ui
+                            .text_edit_singleline(&mut self.ui_state.custom_rule)
+                            .on_hover_text(
+                                "For example B3/S23, 23/3, B2/S34H, B2/S/C3, B3/S23-a4i \
+                                 or R5,C0,M1,S34..58,B34..45,NM",
+                            );
+
+                        if !self.ui_state.custom_rule.trim().is_empty() {
+                            match self.ui_state.custom_rule.parse::<Rule>() {
+                                Ok(rule) => {
+                                    if response.changed() {
+                                        self.config.rule = rule;
+                                    }
+                                }
+                                Err(e) => {
+                                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+
+                                    if response.lost_focus()
+                                        && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                                    {
+                                        notify_warning!(self, "Invalid rule: {}", e);
+                                    }
+                                }
+                            }
+                        }
+
+                        if self.config.rule != rule_before {
+                            self.warn_if_rule_cant_step();
+                        }
+
+                        if let Some(automaton) = match self.config.rule {
+                            Rule::Custom(i) => automaton(i),
+                            _ => None,
+                        } {
+                            let params = automaton.params();
+
+                            if !params.is_empty() {
+                                ui.collapsing(format!("{} parameters", automaton.name()), |ui| {
+                                    let values = self
+                                        .config
+                                        .rule_params
+                                        .entry(automaton.name().to_string())
+                                        .or_default();
+
+                                    param_editor(ui, &params, values);
+                                });
+                            }
+                        }
+
+                        if self.config.rule.is_rulestring() {
+                            ui.add_space(8.);
+
+                            let _ = ComboBox::from_label("Engine")
+                                .selected_text(self.config.engine.to_string())
+                                .show_ui(ui, |ui| {
+                                    for engine in [Engine::Bitwise, Engine::HashLife] {
+                                        ui.selectable_value(
+                                            &mut self.config.engine,
+                                            engine,
+                                            engine.to_string(),
+                                        );
+                                    }
+                                });
+
+                            if let (Engine::HashLife, Some(reason)) = (
+                                self.config.engine,
+                                hashlife_blocker(&self.board, &self.config),
+                            ) {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!("HashLife can't be used because {reason}, so the board steps bitwise."),
+                                );
+                            }
+
+                            usize_slider(
+                                ui,
+                                &mut self.config.hashlife_memory_mb,
+                                64,
+                                8192,
+                                "HashLife memory cap (MB)",
+                            );
+
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::DragValue::new(&mut self.ui_state.jump_generations)
+                                        .range(1..=MAX_JUMP),
+                                );
+                                if ui.button("Jump forward (generations)").clicked() {
+                                    self.jump(self.ui_state.jump_generations);
+                                }
+                            });
+                        }
+                    });
+
+                    ui.collapsing("Rule editor", |ui| {
+                        if let Rule::Margolus(mut rule) = self.config.rule {
+                            ui.horizontal(|ui| {
+                                block_table_editor(ui, &mut rule);
+                                self.ui_state.rule_preview.show(ui, &self.config);
+                            });
+
+                            self.config.rule = Rule::Margolus(rule);
+                        } else if let Rule::Lenia(mut rule) = self.config.rule {
+                            ui.horizontal(|ui| {
+                                lenia_editor(ui, &mut rule);
+                                self.ui_state.rule_preview.show(ui, &self.config);
+                            });
+
+                            self.config.rule = Rule::Lenia(rule);
+                        } else if let Rule::Stochastic(mut rule) = self.config.rule {
+                            ui.horizontal(|ui| {
+                                stochastic_editor(ui, &mut rule);
+                                self.ui_state.rule_preview.show(ui, &self.config);
+                            });
+
+                            self.config.rule = Rule::Stochastic(rule);
+                        } else if let Rule::GrayScott(mut rule) = self.config.rule {
+                            let mut seed = false;
+                            ui.horizontal(|ui| {
+                                seed = gray_scott_editor(ui, &mut rule, &mut self.config.seeding);
+                                self.ui_state.rule_preview.show(ui, &self.config);
+                            });
+
+                            self.config.rule = Rule::GrayScott(rule);
+                            if seed {
+                                self.board.seed_chemicals(self.config.seeding);
+                            }
+                        } else {
+                            let mut life = match self.config.rule {
+                                Rule::Rulestring(rule) => rule,
+                                Rule::Generations(rule) => rule.life,
+                                Rule::Species(rule) => rule.life,
+                                _ => {
+                                    ui.label(
+                                        "Pick a life-like, probabilistic, Generations, multi-species, block, continuous or reaction-diffusion rule to edit it.",
+                                    );
+                                    return;
+                                }
+                            };
+
+                            ui.horizontal(|ui| {
+                                egui::Grid::new("rule_editor").show(ui, |ui| {
+                                    let max = life.neighbourhood.mask().count_ones() as usize;
+
+                                    for (label, counts) in
+                                        [("B", &mut life.spawn), ("S", &mut life.survive)]
+                                    {
+                                        ui.label(label);
+                                        for (n, on) in counts.iter_mut().enumerate() {
+                                            ui.add_enabled_ui(n <= max, |ui| {
+                                                ui.toggle_value(on, n.to_string())
+                                            });
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+
+                                self.ui_state.rule_preview.show(ui, &self.config);
+                            });
+
+                            if self.config.rule.is_rulestring()
+                                && ui.button("Make probabilistic").clicked()
+                            {
+                                self.config.rule = Rule::Stochastic(Stochastic::from(life));
+                            }
+
+                            match &mut self.config.rule {
+                                Rule::Rulestring(rule) => *rule = life,
+                                Rule::Generations(rule) => rule.life = life,
+                                Rule::Species(rule) => rule.life = life,
+                                _ => {}
+                            }
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.ui_state.saved_rule_name);
+
+                            if ui.button("Save rule").clicked() {
+                                let name = self.ui_state.saved_rule_name.trim().to_string();
+
+                                if name.is_empty() {
+                                    notify_warning!(self, "Give the rule a name first.");
+                                    return;
+                                }
+
+                                let rule = self.config.rule;
+                                match self.config.saved_rules.iter_mut().find(|(n, _)| *n == name) {
+                                    Some(saved) => saved.1 = rule,
+                                    None => self.config.saved_rules.push((name.clone(), rule)),
+                                }
+                                notify_info!(self, "Saved rule {} as {}.", rule, name);
+                            }
+                        });
                     });
 
                     ui.collapsing("Recording", |ui| {
@@ -262,11 +590,69 @@ impl Game {
                                 },
                                 "Selection",
                             );
+                            ui.selectable_value(&mut self.selected_tool, Tool::Agent, "Agent");
                         });
 
                     if self.selected_tool.is_brush() {
                         usize_slider(ui, &mut self.config.brush_radius, 1, 10, "Brush radius");
                     }
+
+                    if self.config.rule.is_turmite() {
+                        ui.add(
+                            egui::Slider::new(&mut self.config.agent_steps, 1..=1000)
+                                .logarithmic(true)
+                                .text("Agent steps per generation"),
+                        );
+                    }
+
+                    if self.config.rule.is_falling_sand() {
+                        let _ = ComboBox::from_label("Paint")
+                            .selected_text(self.config.sand_brush.to_string())
+                            .show_ui(ui, |ui| {
+                                for material in MATERIALS {
+                                    ui.selectable_value(
+                                        &mut self.config.sand_brush,
+                                        *material,
+                                        material.to_string(),
+                                    );
+                                }
+                            });
+                    }
+
+                    if let Rule::Species(rule) = self.config.rule {
+                        ui.horizontal(|ui| {
+                            ui.label("Paint");
+                            for species in 0..rule.species {
+                                let color = self.config.species_colors[species as usize];
+                                ui.selectable_value(
+                                    &mut self.config.species_brush,
+                                    species,
+                                    RichText::new(format!("Species {}", species + 1))
+                                        .color(color.to_egui()),
+                                );
+                            }
+                        });
+                    }
+
+                    if self.config.rule.is_wireworld() {
+                        let _ = ComboBox::from_label("Paint")
+                            .selected_text(self.config.wireworld_brush.to_string())
+                            .show_ui(ui, |ui| {
+                                for cell in WIREWORLD_CELLS {
+                                    ui.selectable_value(
+                                        &mut self.config.wireworld_brush,
+                                        *cell,
+                                        cell.to_string(),
+                                    );
+                                }
+                            });
+                    }
+
+                    if self.get_selection().is_some()
+                        && ui.button("Crop board to selection (K)").clicked()
+                    {
+                        self.crop_to_selection();
+                    }
                 });
         });
 
@@ -284,8 +670,190 @@ fn color_picker(ui: &mut Ui, color: &mut GColor, label: &str) {
     });
 }
 
+/// Each block next to the block it turns into, whose cells can be clicked.
+fn block_table_editor(ui: &mut Ui, rule: &mut Margolus) {
+    ui.vertical(|ui| {
+        egui::Grid::new("block_table").show(ui, |ui| {
+            for block in 0..16 {
+                block_cells(ui, &mut (block as u8), false);
+                ui.label("→");
+                block_cells(ui, &mut rule.table[block], true);
+
+                if block % 4 == 3 {
+                    ui.end_row();
+                }
+            }
+        });
+
+        if rule.is_reversible() {
+            ui.label("Reversible");
+        }
+    });
+}
+
+/// Sliders for how heat feeds back into life-like rules.
+fn heat_coupling_editor(ui: &mut Ui, coupling: &mut HeatCoupling) {
+    ui.add(egui::Slider::new(&mut coupling.gain, 0..=255).text("Heat gain per live generation"));
+    ui.add(egui::Slider::new(&mut coupling.decay, 0..=255).text("Heat decay per dead generation"));
+    ui.add(egui::Slider::new(&mut coupling.birth_inhibit, 0..=255).text("Heat that blocks births"));
+    ui.add(egui::Slider::new(&mut coupling.burnout, 0..=255).text("Burnout heat"));
+
+    if ui.button("Maze cycle settings").clicked() {
+        *coupling = HeatCoupling::MAZE_CYCLE;
+    }
+}
+
+/// A slider for the chance of each birth and survival count.
+fn stochastic_editor(ui: &mut Ui, rule: &mut Stochastic) {
+    egui::Grid::new("stochastic_editor").show(ui, |ui| {
+        let max = rule.neighbourhood.mask().count_ones() as usize;
+
+        ui.label("");
+        for n in 0..=max {
+            ui.label(n.to_string());
+        }
+        ui.end_row();
+
+        for (label, chances) in [("B", &mut rule.spawn), ("S", &mut rule.survive)] {
+            ui.label(label);
+            for chance in &mut chances[..=max] {
+                ui.add(
+                    egui::Slider::new(chance, 0.0..=1.)
+                        .vertical()
+                        .show_value(false),
+                )
+                .on_hover_text(format!("{chance:.2}"));
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// Kernel and growth settings of a continuous rule.
+fn lenia_editor(ui: &mut Ui, rule: &mut Lenia) {
+    ui.vertical(|ui| {
+        ComboBox::from_label("Kernel")
+            .selected_text(rule.kernel.to_string())
+            .show_ui(ui, |ui| {
+                for &shape in KERNEL_SHAPES {
+                    ui.selectable_value(&mut rule.kernel, shape, shape.to_string());
+                }
+            });
+
+        ui.add(egui::Slider::new(&mut rule.radius, 1..=MAX_RADIUS).text("Kernel radius"));
+        ui.add(egui::Slider::new(&mut rule.mu, 0.0..=1.).text("Growth mu"));
+        ui.add(
+            egui::Slider::new(&mut rule.sigma, 0.001..=0.5)
+                .logarithmic(true)
+                .text("Growth sigma"),
+        );
+        ui.add(
+            egui::Slider::new(&mut rule.dt, 0.01..=1.)
+                .logarithmic(true)
+                .text("Time step"),
+        );
+    });
+}
+
+/// Rates of a reaction-diffusion rule, and a way to seed the board with B.
+/// Returns whether the board should be seeded.
+fn gray_scott_editor(ui: &mut Ui, rule: &mut GrayScott, seeding: &mut Seeding) -> bool {
+    ui.vertical(|ui| {
+        ui.add(egui::Slider::new(&mut rule.feed, 0.0..=0.1).text("Feed rate"));
+        ui.add(egui::Slider::new(&mut rule.kill, 0.0..=0.1).text("Kill rate"));
+        ui.add(egui::Slider::new(&mut rule.diffuse_a, 0.0..=1.).text("Diffusion of A"));
+        ui.add(egui::Slider::new(&mut rule.diffuse_b, 0.0..=1.).text("Diffusion of B"));
+
+        ui.horizontal(|ui| {
+            ComboBox::from_label("Seed with")
+                .selected_text(seeding.to_string())
+                .show_ui(ui, |ui| {
+                    for &option in SEEDINGS {
+                        ui.selectable_value(seeding, option, option.to_string());
+                    }
+                });
+
+            ui.button("Seed board").clicked()
+        })
+        .inner
+    })
+    .inner
+}
+
+/// A block as a 2x2 grid of toggles.
+fn block_cells(ui: &mut Ui, block: &mut u8, enabled: bool) {
+    ui.vertical(|ui| {
+        ui.spacing_mut().item_spacing = egui::vec2(1., 1.);
+
+        for row in [[1, 2], [4, 8]] {
+            ui.horizontal(|ui| {
+                for bit in row {
+                    let on = *block & bit != 0;
+
+                    if ui
+                        .add_enabled(enabled, egui::SelectableLabel::new(on, "  "))
+                        .clicked()
+                    {
+                        *block ^= bit;
+                    }
+                }
+            });
+        }
+    });
+}
+
 fn usize_slider(ui: &mut Ui, value: &mut usize, min: usize, max: usize, label: &str) {
     let mut value_f32 = *value as f32;
     ui.add(egui::Slider::new(&mut value_f32, min as f32..=max as f32).text(label));
     *value = value_f32 as usize;
 }
+
+/// A widget for each of an automaton's parameters, keeping changed values in
+/// `values`.
+fn param_editor(ui: &mut Ui, params: &[Param], values: &mut BTreeMap<String, ParamValue>) {
+    let current = Params::new(params, Some(values));
+
+    for param in params {
+        let changed = match &param.kind {
+            ParamKind::Float { range, .. } => {
+                let mut value = current.float(param.name);
+                let logarithmic = *range.start() > 0. && *range.end() / *range.start() >= 100.;
+
+                ui.add(
+                    egui::Slider::new(&mut value, range.clone())
+                        .logarithmic(logarithmic)
+                        .text(param.name),
+                )
+                .changed()
+                .then_some(ParamValue::Float(value))
+            }
+            ParamKind::Int { range, .. } => {
+                let mut value = current.int(param.name);
+                let logarithmic = *range.start() > 0 && *range.end() / *range.start() >= 100;
+
+                ui.add(
+                    egui::Slider::new(&mut value, range.clone())
+                        .logarithmic(logarithmic)
+                        .text(param.name),
+                )
+                .changed()
+                .then_some(ParamValue::Int(value))
+            }
+            ParamKind::Bool { .. } => {
+                let mut value = current.bool(param.name);
+
+                ui.checkbox(&mut value, param.name)
+                    .changed()
+                    .then_some(ParamValue::Bool(value))
+            }
+        };
+
+        if let Some(value) = changed {
+            values.insert(param.name.to_string(), value);
+        }
+    }
+
+    if ui.button("Reset to defaults").clicked() {
+        values.clear();
+    }
+}
@@ -1,3 +1,4 @@
+use egui_macroquad::egui::Color32;
 use image::{Rgb, Rgba};
 use macroquad::color::Color;
 use serde::{Deserialize, Serialize};
@@ -87,4 +88,8 @@ impl GColor {
     pub fn to_img(self) -> Rgba<u8> {
         Rgba([self.r_u8(), self.g_u8(), self.b_u8(), self.a_u8()])
     }
+
+    pub fn to_egui(self) -> Color32 {
+        Color32::from_rgba_unmultiplied(self.r_u8(), self.g_u8(), self.b_u8(), self.a_u8())
+    }
 }
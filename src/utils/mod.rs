@@ -1,15 +1,9 @@
-use macroquad::rand::rand;
-
 pub use color::*;
 pub use tiny_str::*;
 
 mod color;
 mod tiny_str;
 
-pub fn rand_bool() -> bool {
-    rand() % 2 == 0
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Vec2I {
     pub x: isize,
@@ -29,3 +23,11 @@ macro_rules! notify_info {
         log::info!($($arg)*);
     }}
 }
+
+#[macro_export]
+macro_rules! notify_warning {
+    ($game: expr, $($arg:tt)*) => {{
+        $game.notifications.warning(&format!($($arg)*));
+        log::warn!($($arg)*);
+    }}
+}